@@ -0,0 +1,320 @@
+use std::io::{BufRead, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Maximum number of commands kept in the history file
+const HISTORY_SIZE: usize = 1000;
+
+/// Terminal in non canonical mode, without echo nor signals, until dropped
+pub struct RawMode(String);
+impl RawMode {
+    pub fn enable() -> Option<Self> {
+        let saved = Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        let saved = String::from_utf8(saved.stdout).ok()?.trim().to_owned();
+        stty(&["-icanon", "-echo", "-isig", "min", "1"]).then_some(Self(saved))
+    }
+}
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&[&self.0]);
+    }
+}
+
+fn stty(args: &[&str]) -> bool {
+    Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    /// Ctrl-U
+    KillLine,
+    /// Ctrl-W
+    KillWord,
+    /// Ctrl-D
+    EndOfFile,
+    /// Ctrl-C
+    Interrupt,
+    Unknown,
+}
+
+fn read_byte(input: &mut impl Read) -> Option<u8> {
+    let mut byte = [0];
+    match input.read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        _ => None,
+    }
+}
+
+fn read_key(input: &mut impl Read) -> Option<Key> {
+    let key = match read_byte(input)? {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7f | 0x08 => Key::Backspace,
+        0x01 => Key::Home,
+        0x05 => Key::End,
+        0x02 => Key::Left,
+        0x06 => Key::Right,
+        0x10 => Key::Up,
+        0x0e => Key::Down,
+        0x15 => Key::KillLine,
+        0x17 => Key::KillWord,
+        0x04 => Key::EndOfFile,
+        0x03 => Key::Interrupt,
+        0x1b => {
+            let kind = read_byte(input)?;
+            if kind != b'[' && kind != b'O' {
+                return Some(Key::Unknown);
+            }
+            let mut param = 0;
+            loop {
+                match read_byte(input)? {
+                    b @ b'0'..=b'9' => param = param * 10 + (b - b'0') as u32,
+                    b';' => {}
+                    b'A' => break Key::Up,
+                    b'B' => break Key::Down,
+                    b'C' => break Key::Right,
+                    b'D' => break Key::Left,
+                    b'H' => break Key::Home,
+                    b'F' => break Key::End,
+                    b'~' => {
+                        break match param {
+                            1 | 7 => Key::Home,
+                            4 | 8 => Key::End,
+                            3 => Key::Delete,
+                            _ => Key::Unknown,
+                        }
+                    }
+                    _ => break Key::Unknown,
+                }
+            }
+        }
+        b if b < 0x20 => Key::Unknown,
+        b => {
+            let len = match b {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            let mut bytes = vec![b];
+            for _ in 1..len {
+                bytes.push(read_byte(input)?);
+            }
+            match std::str::from_utf8(&bytes).ok().and_then(|s| s.chars().next()) {
+                Some(c) => Key::Char(c),
+                None => Key::Unknown,
+            }
+        }
+    };
+    Some(key)
+}
+
+/// Longest common prefix of the candidates
+fn common_prefix(candidates: &[String]) -> &str {
+    let first = &candidates[0];
+    let mut len = first.len();
+    for c in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(c.chars())
+            .find(|((_, a), b)| a != b)
+            .map(|((i, _), _)| i)
+            .unwrap_or_else(|| first.len().min(c.len()))
+            .min(len);
+    }
+    &first[..len]
+}
+
+/// Reads the commands of the user, with line editing, a history saved in
+/// `~/.prove_history` and tab completion, when the terminal is in raw mode
+pub struct Editor {
+    history: Vec<String>,
+    path: Option<PathBuf>,
+    raw: bool,
+}
+impl Editor {
+    /// `raw` tells whether the terminal was put in raw mode for the session,
+    /// lines are read as they come otherwise
+    pub fn new(raw: bool) -> Self {
+        let path = std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".prove_history"));
+        let mut history: Vec<String> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|s| s.lines().filter(|l| !l.is_empty()).map(str::to_owned).collect())
+            .unwrap_or_default();
+        let skip = history.len().saturating_sub(HISTORY_SIZE);
+        history.drain(..skip);
+        Self { history, path, raw }
+    }
+
+    /// Adds the line to the history and rewrites the history file with the
+    /// last `HISTORY_SIZE` commands, only lines typed in raw mode are kept,
+    /// not the scripts piped to `solve`
+    fn remember(&mut self, line: &str) {
+        if !self.raw || line.is_empty() || self.history.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        self.history.push(line.to_owned());
+        let skip = self.history.len().saturating_sub(HISTORY_SIZE);
+        self.history.drain(..skip);
+        if let Some(path) = &self.path {
+            let mut content = self.history.join("\n");
+            content.push('\n');
+            let _ = std::fs::write(path, content);
+        }
+    }
+
+    /// Reads a line after the prompt, starting with the `initial` text to
+    /// edit, `complete` gives the candidates to complete the text before the
+    /// cursor with the offset where they start, `None` at the end of the input
+    pub fn read_line(
+        &mut self,
+        prompt: &str,
+        initial: &str,
+        complete: impl Fn(&str) -> (usize, Vec<String>),
+    ) -> Option<String> {
+        let line = if self.raw {
+            self.edit(prompt, initial, complete)?
+        } else {
+            print!("{}", prompt);
+            let _ = std::io::stdout().flush();
+            let mut buffer = String::new();
+            if let Ok(0) | Err(_) = std::io::stdin().lock().read_line(&mut buffer) {
+                return None;
+            }
+            // in place of the echo of the terminal
+            println!();
+            buffer
+        };
+        let line = line.trim().to_owned();
+        self.remember(&line);
+        Some(line)
+    }
+
+    fn edit(&self, prompt: &str, initial: &str, complete: impl Fn(&str) -> (usize, Vec<String>)) -> Option<String> {
+        let stdin = std::io::stdin();
+        let mut input = stdin.lock();
+        let mut line: Vec<char> = initial.chars().collect();
+        let mut cursor = line.len();
+        // position in the history, the line being written is saved when
+        // browsing the history
+        let mut index = self.history.len();
+        let mut draft = Vec::new();
+        loop {
+            redraw(prompt, &line, cursor);
+            match read_key(&mut input)? {
+                Key::Enter => {
+                    println!();
+                    return Some(line.into_iter().collect());
+                }
+                Key::EndOfFile if line.is_empty() => {
+                    println!();
+                    return None;
+                }
+                Key::Interrupt => {
+                    println!();
+                    return None;
+                }
+                Key::EndOfFile | Key::Delete if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Key::Char(c) => {
+                    line.insert(cursor, c);
+                    cursor += 1;
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                Key::Left if cursor > 0 => cursor -= 1,
+                Key::Right if cursor < line.len() => cursor += 1,
+                Key::Home => cursor = 0,
+                Key::End => cursor = line.len(),
+                Key::KillLine => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                }
+                Key::KillWord => {
+                    let mut start = cursor;
+                    while start > 0 && line[start - 1] == ' ' {
+                        start -= 1;
+                    }
+                    while start > 0 && line[start - 1] != ' ' {
+                        start -= 1;
+                    }
+                    line.drain(start..cursor);
+                    cursor = start;
+                }
+                key @ (Key::Up | Key::Down) => {
+                    let next = match key {
+                        Key::Up => index.checked_sub(1),
+                        _ => Some(index + 1).filter(|i| *i <= self.history.len()),
+                    };
+                    if let Some(next) = next {
+                        if index == self.history.len() {
+                            draft = line.clone();
+                        }
+                        index = next;
+                        line = match self.history.get(index) {
+                            Some(command) => command.chars().collect(),
+                            None => draft.clone(),
+                        };
+                        cursor = line.len();
+                    }
+                }
+                Key::Tab => {
+                    let before: String = line[..cursor].iter().collect();
+                    let (start, candidates) = complete(&before);
+                    let typed = &before[start..];
+                    let candidates: Vec<String> =
+                        candidates.into_iter().filter(|c| c.starts_with(typed)).collect();
+                    if candidates.is_empty() {
+                        continue;
+                    }
+                    let prefix = common_prefix(&candidates);
+                    let mut insert: Vec<char> = prefix[typed.len()..].chars().collect();
+                    if candidates.len() == 1 {
+                        insert.push(' ');
+                    }
+                    // the candidates are listed on the line above
+                    if insert.is_empty() {
+                        print!("\x1b[A\r{}\x1b[K\x1b[B", candidates.join("    "));
+                    }
+                    let n = insert.len();
+                    line.splice(cursor..cursor, insert);
+                    cursor += n;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn redraw(prompt: &str, line: &[char], cursor: usize) {
+    let text: String = line.iter().collect();
+    print!("\r{}{}\x1b[K", prompt, text);
+    if cursor < line.len() {
+        print!("\x1b[{}D", line.len() - cursor);
+    }
+    let _ = std::io::stdout().flush();
+}
@@ -0,0 +1,191 @@
+use crate::session::Status;
+use prove::{search, Problem, Proof, ReprConf};
+use std::fmt::Write;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Largest proof searched for the problems which were not solved, the
+/// search stops at the size of the proof of the solved ones
+const SEARCH_SIZE: usize = 20;
+
+/// Work on one problem of an exercise sheet
+pub struct Record {
+    key: String,
+    sequent: String,
+    status: Status,
+    /// Rules applied
+    rules: usize,
+    /// Steps undone with `:b`
+    undos: usize,
+    /// Rules that could not be applied
+    failures: usize,
+    started: Instant,
+    /// Time taken to solve the problem, or spent on it until it was left
+    time: Option<Duration>,
+    /// Size of the proof when solved
+    size: Option<usize>,
+    /// Size of the smallest proof found by search
+    smallest: Option<usize>,
+    /// Search of the smallest proof, run in the background while the next
+    /// problems are solved
+    search: Option<JoinHandle<Option<usize>>>,
+}
+impl Record {
+    /// Starts recording the work on the problem, and the time taken
+    pub fn new(key: &str, problem: &Problem) -> Self {
+        let conf = ReprConf {
+            negation: true,
+            ..Default::default()
+        };
+        Self {
+            key: key.to_owned(),
+            sequent: problem.sequent.repr_conf(conf).to_string(),
+            status: Status::InProgress,
+            rules: 0,
+            undos: 0,
+            failures: 0,
+            started: Instant::now(),
+            time: None,
+            size: None,
+            smallest: None,
+            search: None,
+        }
+    }
+    pub fn rule(&mut self) {
+        self.rules += 1;
+    }
+    pub fn undo(&mut self) {
+        self.undos += 1;
+    }
+    pub fn failure(&mut self) {
+        self.failures += 1;
+    }
+    /// Stops the clock the first time the problem is solved
+    pub fn solved(&mut self) {
+        let started = self.started;
+        self.time.get_or_insert_with(|| started.elapsed());
+    }
+    /// Ends the record when the problem is left, with its proof at that time
+    pub fn finish(mut self, status: Status, problem: &Problem, proof: &Proof) -> Self {
+        self.solved();
+        self.status = status;
+        if proof.is_complete() {
+            self.size = Some(proof.size());
+        }
+        let max_size = self.size.unwrap_or(SEARCH_SIZE);
+        let problem = problem.clone();
+        self.search = Some(thread::spawn(move || search(&problem, max_size).map(|proof| proof.size())));
+        self
+    }
+    /// Waits for the search of the smallest proof
+    pub fn wait(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.smallest = search.join().unwrap_or(None);
+        }
+    }
+}
+
+fn optional(n: Option<usize>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_else(|| "-".to_owned())
+}
+
+fn duration(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Table of the records of a session, eg:
+///
+/// ```text
+/// problem       status       rules  undos  fails   time  size  best
+/// contrapose    solved           6      1      0   1:02     5     5
+/// 2             skipped          3      0      2   0:40     -     4
+/// ```
+pub fn summary(records: &[Record]) -> String {
+    let width = records.iter().map(|r| r.key.chars().count()).chain(Some(7)).max().unwrap_or(0);
+    let mut table = format!(
+        "{:<width$}  {:<11}  {:>5}  {:>5}  {:>5}  {:>5}  {:>4}  {:>4}\n",
+        "problem",
+        "status",
+        "rules",
+        "undos",
+        "fails",
+        "time",
+        "size",
+        "best",
+        width = width,
+    );
+    for r in records {
+        let _ = writeln!(
+            table,
+            "{:<width$}  {:<11}  {:>5}  {:>5}  {:>5}  {:>5}  {:>4}  {:>4}",
+            r.key,
+            r.status.to_string(),
+            r.rules,
+            r.undos,
+            r.failures,
+            duration(r.time.unwrap_or_default()),
+            optional(r.size),
+            optional(r.smallest),
+            width = width,
+        );
+    }
+    table
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_number(n: Option<usize>) -> String {
+    n.map(|n| n.to_string()).unwrap_or_else(|| "null".to_owned())
+}
+
+/// Records of a session as JSON, eg:
+///
+/// ```text
+/// {"problems": [
+///   {"problem": "contrapose", "sequent": "~P->~Q |- ~~Q->~~P",
+///    "status": "solved", "rules": 6, "undos": 1, "failures": 0,
+///    "seconds": 62.1, "size": 5, "smallest": 5}
+/// ]}
+/// ```
+///
+/// `size` is `null` when the problem was not solved, and `smallest` when the
+/// search found no proof.
+pub fn report(records: &[Record]) -> String {
+    let mut json = String::from("{\"problems\": [");
+    for (i, r) in records.iter().enumerate() {
+        let _ = write!(
+            json,
+            "{}\n  {{\"problem\": {}, \"sequent\": {}, \"status\": {}, \"rules\": {}, \
+             \"undos\": {}, \"failures\": {}, \"seconds\": {:.1}, \"size\": {}, \
+             \"smallest\": {}}}",
+            if i == 0 { "" } else { "," },
+            json_string(&r.key),
+            json_string(&r.sequent),
+            json_string(&r.status.to_string()),
+            r.rules,
+            r.undos,
+            r.failures,
+            r.time.unwrap_or_default().as_secs_f64(),
+            json_number(r.size),
+            json_number(r.smallest),
+        );
+    }
+    json.push_str("\n]}\n");
+    json
+}
@@ -0,0 +1,145 @@
+use prove::{Context, Lemma, Proof, Script};
+use std::path::PathBuf;
+
+/// Lemmas proven in `solve`, saved in `~/.prove_lemmas`, eg:
+///
+/// ```text
+/// lemma syl
+/// A->B, B->C |- A->C
+/// ii
+/// e 1
+/// e 0
+/// h
+/// ```
+///
+/// Each lemma is followed by the script of its proof, which may use the
+/// lemmas above it.
+pub struct Library {
+    path: Option<PathBuf>,
+    lemmas: Vec<Lemma>,
+    /// `None` for the theorems imported by the problem file, which are not
+    /// saved
+    scripts: Vec<Option<Script>>,
+}
+
+/// Whether the name can be given to a lemma, dots are allowed to name
+/// lemmas like the problems of imported theories, eg: `basics.syl`
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some('A'..='Z') | Some('a'..='z') | Some('_'))
+        && chars.all(|c| matches!(c, 'A'..='Z' | 'a'..='z' | '0'..='9' | '_' | '.'))
+}
+
+impl Library {
+    /// Lemmas saved in `~/.prove_lemmas`, none if there is no such file
+    pub fn load() -> Result<Self, String> {
+        Self::load_from(std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".prove_lemmas")))
+    }
+
+    /// No lemma, and nowhere to save them
+    pub fn empty() -> Self {
+        Self {
+            path: None,
+            lemmas: Vec::new(),
+            scripts: Vec::new(),
+        }
+    }
+
+    /// Lemmas saved in the file, none if there is no such file, or no file
+    pub fn load_from(path: Option<PathBuf>) -> Result<Self, String> {
+        let mut library = Self { path, ..Self::empty() };
+        let content = match library.path.as_ref().map(std::fs::read_to_string) {
+            Some(Ok(content)) => content,
+            _ => return Ok(library),
+        };
+        let display = library.path.as_ref().unwrap().display().to_string();
+        let mut headers = Vec::new();
+        let mut at = 0;
+        for (i, line) in content.split_inclusive('\n').enumerate() {
+            if let Some(name) = line.strip_prefix("lemma ") {
+                headers.push((i + 1, at, at + line.len(), name.trim()));
+            }
+            at += line.len();
+        }
+        if let Some(start) = content.lines().position(|l| !l.trim().is_empty()) {
+            if headers.first().map(|h| h.0) != Some(start + 1) {
+                return Err(format!("{}:{}: expecting `lemma <NAME>`", display, start + 1));
+            }
+        }
+        for (n, (line, _, start, name)) in headers.iter().enumerate() {
+            let end = headers.get(n + 1).map(|h| h.1).unwrap_or(content.len());
+            let script: Script = content[*start..end]
+                .parse()
+                .map_err(|e: prove::ScriptError| format!("{}:{}: {}", display, line + e.line, e.error))?;
+            let context = Context {
+                lemmas: &library.lemmas,
+                definitions: &[],
+            };
+            let proof = Proof::replay_with(&script, context)
+                .map_err(|e| format!("{}:{}: lemma `{}`, {}", display, line, name, e))?;
+            library
+                .add(name, proof, &script)
+                .map_err(|e| format!("{}:{}: lemma `{}`, {}", display, line, name, e))?;
+        }
+        Ok(library)
+    }
+
+    pub fn lemmas(&self) -> &[Lemma] {
+        &self.lemmas
+    }
+
+    /// Adds the lemma proven by the proof, which was built by the script
+    pub fn add(&mut self, name: &str, proof: Proof, script: &Script) -> Result<(), &'static str> {
+        if !is_name(name) {
+            return Err("invalid lemma name");
+        }
+        if self.lemmas.iter().any(|l| l.name() == name) {
+            return Err("lemma already defined");
+        }
+        let lemma = Lemma::new(name, proof).ok_or("proof not complete")?;
+        // neither the definitions nor the imported theorems of the problem
+        // files are saved with the lemmas
+        let saved: Vec<Lemma> = self
+            .lemmas
+            .iter()
+            .zip(&self.scripts)
+            .filter(|(_, script)| script.is_some())
+            .map(|(lemma, _)| lemma.clone())
+            .collect();
+        let context = Context {
+            lemmas: &saved,
+            definitions: &[],
+        };
+        if !Proof::replay_with(script, context).is_ok_and(|p| p.is_complete()) {
+            return Err("proofs using definitions or imported theorems can't be saved");
+        }
+        self.lemmas.push(lemma);
+        self.scripts.push(Some(script.clone()));
+        Ok(())
+    }
+
+    /// Adds a theorem of an imported problem file, proven in its session,
+    /// it is not saved
+    pub fn import(&mut self, name: &str, proof: Proof) -> Result<(), &'static str> {
+        if self.lemmas.iter().any(|l| l.name() == name) {
+            return Err("lemma already defined");
+        }
+        self.lemmas.push(Lemma::new(name, proof).ok_or("proof not complete")?);
+        self.scripts.push(None);
+        Ok(())
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut content = String::new();
+        for (lemma, script) in self.lemmas.iter().zip(&self.scripts) {
+            if let Some(script) = script {
+                content += &format!("lemma {}\n{}", lemma.name(), script);
+            }
+        }
+        std::fs::write(path, content)
+    }
+}
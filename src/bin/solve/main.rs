@@ -0,0 +1,999 @@
+mod editor;
+mod exercise;
+mod lemmas;
+mod session;
+mod tui;
+mod undo;
+mod viewport;
+
+use editor::Editor;
+use exercise::Record;
+use lemmas::Library;
+use session::{Session, Status};
+use tui::{Frame, Message, Overlay, Screen};
+use undo::UndoTree;
+use viewport::{Direction, Viewport};
+use prove::*;
+
+enum Command {
+    Rule(ProveBy),
+    Restart,
+    Back,
+    Skip,
+    Help,
+    Negation,
+    Hint,
+    Focus(usize),
+    Tree,
+    Goto(String),
+    Mark(String),
+    Lemma(String),
+    Lemmas,
+    Pan(Direction, Option<usize>),
+    Center,
+    Write(String),
+    Quit,
+}
+const COMMANDS: &[&str] = &[
+    ":b", ":r", ":s", ":h", ":n", ":t", ":focus", ":tree", ":goto", ":mark", ":lemma", ":lemmas", ":left", ":right",
+    ":up", ":down", ":center", ":w", ":q",
+];
+
+fn direction(command: &str) -> Option<Direction> {
+    match command {
+        ":left" => Some(Direction::Left),
+        ":right" => Some(Direction::Right),
+        ":up" => Some(Direction::Up),
+        ":down" => Some(Direction::Down),
+        _ => None,
+    }
+}
+
+impl std::str::FromStr for Command {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.chars().next() {
+            Some(':') => {
+                match s.split_once(' ') {
+                    Some((":w", path)) => return Ok(Command::Write(path.trim().to_owned())),
+                    Some((":goto", target)) => return Ok(Command::Goto(target.trim().to_owned())),
+                    Some((":mark", name)) => return Ok(Command::Mark(name.trim().to_owned())),
+                    Some((":lemma", name)) => return Ok(Command::Lemma(name.trim().to_owned())),
+                    Some((":focus", n)) | Some((":f", n)) => {
+                        let at = s.len() - n.len();
+                        let n = n.trim();
+                        return n.parse().map(Command::Focus).map_err(|_| {
+                            ParseError::new(at..s.len(), "expecting the number of a goal")
+                                .expected(&["number"])
+                                .found(n)
+                        });
+                    }
+                    Some((command, n)) if direction(command).is_some() => {
+                        let at = s.len() - n.len();
+                        let n = n.trim();
+                        let pan = |n| Command::Pan(direction(command).unwrap(), Some(n));
+                        return n.parse().map(pan).map_err(|_| {
+                            ParseError::new(at..s.len(), "expecting a number of columns or lines")
+                                .expected(&["number"])
+                                .found(n)
+                        });
+                    }
+                    _ => {}
+                }
+                if let Some(direction) = direction(s) {
+                    return Ok(Command::Pan(direction, None));
+                }
+                match s {
+                    ":b" => Ok(Command::Back),
+                    ":r" => Ok(Command::Restart),
+                    ":s" => Ok(Command::Skip),
+                    ":h" => Ok(Command::Help),
+                    ":n" => Ok(Command::Negation),
+                    ":t" => Ok(Command::Hint),
+                    ":tree" => Ok(Command::Tree),
+                    ":lemmas" => Ok(Command::Lemmas),
+                    ":center" => Ok(Command::Center),
+                    ":q" => Ok(Command::Quit),
+                    _ => Err(ParseError::new(0..s.len(), "unknown command")
+                        .expected(COMMANDS)
+                        .found(s)),
+                }
+            }
+            Some(_) => Ok(Command::Rule(s.parse()?)),
+            None => Err(ParseError::new(0..0, "empty input")),
+        }
+    }
+}
+
+const USAGE: &str = "\
+COMMANDS
+  :b            back one step, undo the last action
+  :r            reset all steps, undo all actions
+  :tree         list all the explored steps, undone ones included
+  :goto <S>     go back to the state numbered S in :tree, or marked S
+  :mark <NAME>  mark the current state, to come back with :goto NAME
+  :lemma <NAME> save the solved sequent as a lemma, to prove others with
+                `use NAME`
+  :lemmas       list the saved lemmas
+  :s            skip to the next sequent
+  :h            print this help message
+  :n            toggle on/off the negation representaion
+  :t            show the hint of the problem
+  :focus <N>    work on the Nth open goal (:f <N> for short)
+  :left [N]     show N more columns on the left of a large proof, half a
+                screen by default, same for :right, :up and :down, which
+                also scroll this help
+  :center       center the view of a large proof back on the goal
+  :w <FILE>     write the proof script to FILE (eg: proof.prv)
+  :q            quit the program (or ctrl-c, ctrl-d)
+
+APPLICABLE RULES
+  h             hypothesis
+  i             introduction of the conclusion (automatic: it choses
+                introduction rule base on conclusion type)
+  xf            exflaso
+  e <N>         elimination of the Nth hypothesis (automatic: it choses
+                elimination rule base on hypothesis type)
+  ii            implication introduction
+  iis           implications introduction (for chaining implications)
+  dil           disjonction introduction left
+  dir           disjonction introduction right
+  mp <F>        modus ponens on F (a logical property formula like: ~P/\\Q)
+  de <F>, <F>   disjonction elimination of left formula and right formula
+  ce <F>, <F>   conjonction elimination of left formula and right formula
+  raa           reductio ad absurdum (classical logic only)
+  use <NAME> [<V>:=<F>, ...]
+                apply the lemma NAME, its conclusion must match the goal's
+                one and its hypotheses become the goals to prove, with F for
+                the variables V which are not in its conclusion
+  unfold <NAME> [N]
+                replace the connective NAME defined in the problem file by
+                its body, in the conclusion or in the Nth hypothesis
+  fold <NAME> [N]
+                replace the body of the connective NAME by the connective";
+
+/// The commands and the rules, with their headers in bold
+fn usage(color: bool) -> Vec<String> {
+    USAGE
+        .lines()
+        .map(|line| {
+            if color && !line.is_empty() && !line.starts_with(' ') {
+                format!("\x1b[1m{}\x1b[0m", line)
+            } else {
+                line.to_owned()
+            }
+        })
+        .collect()
+}
+
+fn subformulas<'a>(prop: &'a Prop, formulas: &mut Vec<&'a Prop>) {
+    if formulas.contains(&prop) {
+        return;
+    }
+    formulas.push(prop);
+    match prop {
+        Prop::False | Prop::Variable(_) => {}
+        Prop::Conjonction(lhs, rhs)
+        | Prop::Disjonction(lhs, rhs)
+        | Prop::Implication(lhs, rhs)
+        | Prop::Equivalence(lhs, rhs) => {
+            subformulas(lhs, formulas);
+            subformulas(rhs, formulas);
+        }
+        Prop::Defined(_, args) => args.iter().for_each(|arg| subformulas(arg, formulas)),
+    }
+}
+
+/// Candidates to complete the text before the cursor, with the offset where
+/// they start: commands and rules for the first word, and the formulas of the
+/// goal for the arguments of `mp`, `de` and `ce`, lemmas and connectives for
+/// `use`, `unfold` and `fold`
+fn complete(before: &str, goal: Option<&Sequent>, context: Context<'_>) -> (usize, Vec<String>) {
+    match before.split_once(' ') {
+        Some(("use", _)) => (4, context.lemmas.iter().map(|l| l.name().to_owned()).collect()),
+        Some((rule @ ("unfold" | "fold"), _)) => (
+            rule.len() + 1,
+            context.definitions.iter().map(|d| d.name().to_owned()).collect(),
+        ),
+        None => (0, COMMANDS.iter().chain(ProveBy::NAMES).map(|s| s.to_string()).collect()),
+        Some(("mp" | "de" | "ce", _)) => {
+            let start = before.rfind([' ', ',']).map(|i| i + 1).unwrap_or(0);
+            let mut formulas = Vec::new();
+            if let Some(goal) = goal {
+                goal.hypotheses()
+                    .iter()
+                    .chain(Some(goal.conclusion()))
+                    .for_each(|p| subformulas(p, &mut formulas));
+            }
+            let conf = ReprConf {
+                negation: true,
+                ..Default::default()
+            };
+            (start, formulas.iter().map(|p| p.repr_conf(conf).to_string()).collect())
+        }
+        Some(_) => (0, Vec::new()),
+    }
+}
+
+/// Prompt of the command line
+const PROMPT: &str = "> ";
+
+/// Next command, `None` at the end of the input, `initial` is the text left
+/// on the command line to fix it
+fn user_input(editor: &mut Editor, initial: &str, goal: Option<&Sequent>, context: Context<'_>) -> Option<String> {
+    editor.read_line(PROMPT, initial, |before| complete(before, goal, context))
+}
+
+/// Lemmas of the file given with `--lemmas`, or of `~/.prove_lemmas`
+fn load_library(options: &Options) -> Library {
+    let library = match &options.lemmas {
+        Some(path) => Library::load_from(Some(path.into())),
+        None => Library::load(),
+    };
+    library.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("fix or remove the lemma file");
+        std::process::exit(2);
+    })
+}
+
+fn lemma_lines(library: &Library, conf: ReprConf) -> Vec<String> {
+    if library.lemmas().is_empty() {
+        return vec!["no lemma yet, save a solved sequent with `:lemma <NAME>`".to_owned()];
+    }
+    let width = library.lemmas().iter().map(|l| l.name().chars().count()).max().unwrap_or(0);
+    library
+        .lemmas()
+        .iter()
+        .map(|lemma| format!("  {:<width$}  {}", lemma.name(), lemma.sequent().repr_conf(conf), width = width))
+        .collect()
+}
+
+/// Carets under the faulty part of the command line, followed by the error
+fn parse_error(e: &ParseError, input: &str) -> Message {
+    let snippet = e.snippet(input).to_string();
+    let carets = snippet.lines().nth(1).unwrap_or("");
+    Message::Error(format!("{}{} {}", " ".repeat(PROMPT.len()), carets, e))
+}
+
+/// Problems of the file, and the problems of the files it imports
+fn load_problems(path: &str) -> (Vec<Problem>, Vec<Problem>) {
+    if path.ends_with(".p") {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(2);
+            }
+        };
+        return match Sequent::from_tptp(&content) {
+            Ok(sequent) => (vec![Problem::new(sequent)], Vec::new()),
+            Err(e) => {
+                let line = content[..e.span.start].matches('\n').count() + 1;
+                eprintln!("{}:{}: {}", path, line, e);
+                eprintln!("{}", e.snippet(&content));
+                std::process::exit(2);
+            }
+        };
+    }
+    match Theory::load(path) {
+        Ok(theory) => (theory.problems, theory.imported),
+        Err(e) => {
+            eprintln!("{}", e);
+            if let LoadError::Parse { source, error, .. } = &e {
+                eprintln!("{}", error.error.snippet(source));
+            }
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Title bar of the problem: its name and its settings
+fn problem_title(key: &str, problem: &Problem) -> String {
+    let mut info = vec![match &problem.name {
+        Some(name) => format!("theorem {}", name),
+        None => format!("problem {}", key),
+    }];
+    if let Some(difficulty) = problem.difficulty {
+        info.push(format!("difficulty {}", difficulty));
+    }
+    if problem.logic != Logic::default() {
+        info.push(format!("{} logic", problem.logic));
+    }
+    if let Some(rules) = &problem.rules {
+        info.push(format!("rules: {}", rules.join(", ")));
+    }
+    format!(" {}", info.join(" - "))
+}
+
+/// The problems with a marker of their status, the `current` one
+/// highlighted
+fn problem_list(problems: &[(String, Problem)], statuses: &[Option<Status>], current: usize, color: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (i, ((key, _), status)) in problems.iter().zip(statuses).enumerate() {
+        let marker = match status {
+            Some(Status::Solved) => "✓",
+            Some(Status::Skipped) => "-",
+            _ => "·",
+        };
+        lines.push(match (i == current, color) {
+            (true, true) => format!("\x1b[7m> {} {}\x1b[0m", marker, key),
+            (true, false) => format!("> {} {}", marker, key),
+            (false, _) => format!("  {} {}", marker, key),
+        });
+    }
+    lines
+}
+
+/// Lists the open goals when there are several, `target` is the one the
+/// rules are applied on
+fn goal_list(proof: &Proof, goals: &[GoalPath], target: &GoalPath, conf: ReprConf) -> Vec<String> {
+    if goals.len() < 2 {
+        return Vec::new();
+    }
+    let mut lines = vec!["open goals, `:focus <N>` to work on another one".to_owned()];
+    for (i, goal) in goals.iter().enumerate() {
+        let marker = if goal == target { ">" } else { " " };
+        if let Some(p) = proof.goal(goal) {
+            lines.push(format!("{} {:<3}{}", marker, i + 1, p.sequent().repr_conf(conf)));
+        }
+    }
+    lines.push(String::new());
+    lines
+}
+
+/// Hypotheses of the current goal numbered as `e <N>` expects them
+fn goal_panel(goal: &Sequent, conf: ReprConf) -> Vec<String> {
+    let header = "current goal, `e <N>` eliminates the hypothesis numbered N";
+    let mut lines = vec![if conf.formated {
+        format!("\x1b[2m{}\x1b[0m", header)
+    } else {
+        header.to_owned()
+    }];
+    lines.extend(goal.repr_conf(conf).numbered().to_string().lines().map(str::to_owned));
+    lines
+}
+
+/// Scrolls the overlay with `:up` and `:down`, by a third of the screen by
+/// default
+fn scroll(overlay: &mut Overlay, direction: &Direction, n: Option<usize>, rows: usize) {
+    let n = n.unwrap_or(rows / 3);
+    match direction {
+        Direction::Up => overlay.scroll_up(n),
+        Direction::Down => overlay.scroll_down(n),
+        Direction::Left | Direction::Right => {}
+    }
+}
+
+fn write_script(script: &Script, path: &str) -> Message {
+    match std::fs::write(path, script.to_string()) {
+        Ok(()) => Message::Info(format!("script written to {}", path)),
+        Err(e) => Message::Error(format!("can't write the script: {}", e)),
+    }
+}
+
+const CLI_USAGE: &str = "\
+usage: solve [OPTIONS] [FILE] [PROBLEM]
+       solve [OPTIONS] -e <SEQUENT>
+       solve [OPTIONS] --batch <SCRIPT> [FILE] [PROBLEM]
+
+ARGUMENTS
+  FILE              problem file, `sequents.txt` by default, or a TPTP
+                    problem with the `.p` extension
+  PROBLEM           name or index, from 1, of the only problem to prove
+
+OPTIONS
+  -e <SEQUENT>      prove the sequent given inline, eg: -e 'P, P->Q |- Q'
+  --batch <SCRIPT>  replay a proof script, or the standard input with `-`,
+                    without interaction, print the proof or its open goals
+                    and exit with 0 if solved, 1 if unsolved, 2 on errors,
+                    with FILE the script must prove one of its problems, or
+                    PROBLEM, and may use its definitions
+  --unicode         display the formulas with unicode symbols
+  --no-color        display the formulas without colors
+  --logic <LOGIC>   prove in `minimal`, `intuitionistic` or `classical` logic,
+                    instead of the logic of each problem
+  --lemmas <FILE>   read and save the lemmas in FILE instead of
+                    `~/.prove_lemmas`, `--batch` only uses lemmas with it
+  --no-session      neither resume nor save the progress on the problems,
+                    which is saved in `<FILE>.session` otherwise
+  --exercise        record the rules applied, the undos, the failed rules and
+                    the time taken on each problem, and print them at the end
+  --report <FILE>   same as `--exercise`, and write the records to FILE as JSON
+  -h, --help        print this help message";
+
+struct Options {
+    path: Option<String>,
+    problem: Option<String>,
+    sequent: Option<String>,
+    unicode: bool,
+    color: bool,
+    logic: Option<Logic>,
+    lemmas: Option<String>,
+    batch: Option<String>,
+    session: bool,
+    exercise: bool,
+    report: Option<String>,
+}
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            path: None,
+            problem: None,
+            sequent: None,
+            unicode: false,
+            color: true,
+            logic: None,
+            lemmas: None,
+            batch: None,
+            session: true,
+            exercise: false,
+            report: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", CLI_USAGE);
+                    std::process::exit(0);
+                }
+                "-e" => {
+                    let sequent = args.next().ok_or("missing sequent after `-e`")?;
+                    options.sequent = Some(sequent);
+                }
+                "--unicode" => options.unicode = true,
+                "--no-color" => options.color = false,
+                "--no-session" => options.session = false,
+                "--exercise" => options.exercise = true,
+                "--report" => {
+                    let path = args.next().ok_or("missing file after `--report`")?;
+                    options.exercise = true;
+                    options.report = Some(path);
+                }
+                "--batch" => {
+                    let script = args.next().ok_or("missing script after `--batch`")?;
+                    options.batch = Some(script);
+                }
+                "--lemmas" => {
+                    let path = args.next().ok_or("missing file after `--lemmas`")?;
+                    options.lemmas = Some(path);
+                }
+                "--logic" => {
+                    let logic = args.next().ok_or("missing logic after `--logic`")?;
+                    options.logic = Some(logic.parse().map_err(|e: ParseError| e.to_string())?);
+                }
+                arg if arg.starts_with('-') && arg.len() > 1 => {
+                    return Err(format!("unknown option `{}`", arg))
+                }
+                _ if options.path.is_none() => options.path = Some(arg),
+                _ if options.problem.is_none() => options.problem = Some(arg),
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+        if options.sequent.is_some() && options.path.is_some() {
+            return Err("`-e` can't be used with a problem file".to_owned());
+        }
+        if options.batch.is_some() && options.sequent.is_some() {
+            return Err("`--batch` reads its sequent from the script".to_owned());
+        }
+        Ok(options)
+    }
+}
+
+fn cli_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    eprintln!();
+    eprintln!("{}", CLI_USAGE);
+    std::process::exit(2);
+}
+
+fn selection_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(2);
+}
+
+/// Problems selected by the command line, with the name or the index, from
+/// 1, that identifies them in sessions, and the imported problems
+fn select_problems(options: &Options) -> (Vec<(String, Problem)>, Vec<Problem>) {
+    let (problems, imported) = match &options.sequent {
+        Some(input) => match input.parse::<Sequent>() {
+            Ok(sequent) => (vec![Problem::new(sequent)], Vec::new()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                eprintln!("{}", e.snippet(input));
+                std::process::exit(2);
+            }
+        },
+        None => load_problems(options.path.as_deref().unwrap_or("sequents.txt")),
+    };
+    let mut problems: Vec<_> = problems
+        .into_iter()
+        .enumerate()
+        .map(|(i, p)| (p.name.clone().unwrap_or_else(|| (i + 1).to_string()), p))
+        .collect();
+    if let Some(selected) = &options.problem {
+        let index = match selected.parse::<usize>() {
+            Ok(n) if (1..=problems.len()).contains(&n) => n - 1,
+            Ok(_) => selection_error(&format!("no problem number {}, there are {}", selected, problems.len())),
+            Err(_) => problems
+                .iter()
+                .position(|(_, p)| p.name.as_deref() == Some(selected.as_str()))
+                .unwrap_or_else(|| selection_error(&format!("no problem named `{}`", selected))),
+        };
+        problems = vec![problems.swap_remove(index)];
+    }
+    if let Some(logic) = options.logic {
+        problems.iter_mut().for_each(|(_, p)| p.logic = logic);
+    }
+    (problems, imported)
+}
+
+/// Lemmas named after the imported theorems solved in the session of their
+/// file, theorems about defined connectives are left out since their
+/// namespaced sequent is not the one of their session
+fn import_lemmas(library: &mut Library, imported: &[Problem]) {
+    for problem in imported {
+        let (name, path) = match (&problem.name, problem.path.as_ref().and_then(|p| p.to_str())) {
+            (Some(name), Some(path)) => (name, path),
+            _ => continue,
+        };
+        let key = name.rsplit('.').next().unwrap_or(name);
+        let session = match Session::load(path) {
+            Ok(session) => session,
+            Err(_) => continue,
+        };
+        if let Some((Status::Solved, script)) = session.get(key, &problem.sequent) {
+            let context = Context {
+                lemmas: library.lemmas(),
+                definitions: &[],
+            };
+            if let Ok(proof) = Proof::replay_with(script, context) {
+                let _ = library.import(name, proof);
+            }
+        }
+    }
+}
+
+fn open_goals<'a>(proof: &'a Proof, goals: &mut Vec<&'a Sequent>) {
+    match proof.rule() {
+        Some(rule) => rule.hypotheses().iter().for_each(|p| open_goals(p, goals)),
+        None => goals.push(proof.sequent()),
+    }
+}
+
+/// Replays a script without interaction, exits with 0 if the proof is
+/// complete, 1 if goals remain open, and 2 on errors
+fn batch(options: &Options, path: &str) -> ! {
+    use std::io::{IsTerminal, Read};
+    let mut source = String::new();
+    let read = if path == "-" {
+        std::io::stdin().read_to_string(&mut source).map(|_| ())
+    } else {
+        std::fs::read_to_string(path).map(|s| source = s)
+    };
+    if let Err(e) = read {
+        eprintln!("{}: {}", path, e);
+        std::process::exit(2);
+    }
+    let path = if path == "-" { "<stdin>" } else { path };
+    let script = match source.parse::<Script>() {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("{}:{}: {}", path, e.line, e.error);
+            eprintln!("{}", e.error.snippet(&source));
+            std::process::exit(2);
+        }
+    };
+    let problem = match &options.path {
+        Some(file) => select_problems(options)
+            .0
+            .into_iter()
+            .map(|(_, problem)| problem)
+            .find(|problem| &problem.sequent == script.sequent())
+            .unwrap_or_else(|| match &options.problem {
+                Some(selected) => selection_error(&format!("{}: the script does not prove `{}`", path, selected)),
+                None => selection_error(&format!("{}: the script does not prove a problem of {}", path, file)),
+            }),
+        None => Problem {
+            logic: options.logic.unwrap_or_default(),
+            ..Problem::new(script.sequent().clone())
+        },
+    };
+    // grading must not depend on the lemmas of the user running it
+    let library = match &options.lemmas {
+        Some(_) => load_library(options),
+        None => Library::empty(),
+    };
+    let context = Context {
+        lemmas: library.lemmas(),
+        definitions: &problem.definitions,
+    };
+    let forbidden = script.steps().iter().enumerate().find(|(_, s)| !problem.allows_with(&s.rule, context));
+    if let Some((step, s)) = forbidden {
+        if !problem.logic.allows(&s.rule) {
+            eprintln!("{}: step {} `{}`: rule not allowed in {} logic", path, step + 1, s, problem.logic);
+        } else if problem.allows(&s.rule) {
+            eprintln!("{}: step {} `{}`: the lemma uses rules not allowed by the problem", path, step + 1, s);
+        } else {
+            eprintln!("{}: step {} `{}`: rule not allowed by the problem", path, step + 1, s);
+        }
+        std::process::exit(2);
+    }
+    let proof = match Proof::replay_with(&script, context) {
+        Ok(proof) => proof,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(2);
+        }
+    };
+    let conf = ReprConf {
+        negation: true,
+        formated: options.color && std::io::stdout().is_terminal(),
+        unicode: options.unicode,
+        emphazis: false,
+        numbered: false,
+    };
+    print!("{}", proof.repr_conf(conf));
+    let mut goals = Vec::new();
+    open_goals(&proof, &mut goals);
+    if goals.is_empty() {
+        println!("solved");
+        std::process::exit(0);
+    }
+    println!("{} open goal(s):", goals.len());
+    for goal in goals {
+        println!("  {}", goal.repr_conf(conf));
+    }
+    std::process::exit(1);
+}
+
+fn load_session(options: &Options) -> Option<Session> {
+    if !options.session || options.sequent.is_some() {
+        return None;
+    }
+    match Session::load(options.path.as_deref().unwrap_or("sequents.txt")) {
+        Ok(session) => Some(session),
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("{}", e.error.snippet(&e.source));
+            eprintln!("fix or remove the session file, or use --no-session");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Error message when the session can't be saved
+fn save_session(session: &mut Option<Session>, key: &str, status: Status, script: &Script) -> Option<Message> {
+    let session = session.as_mut()?;
+    session.set(key, status, script);
+    let error = session.save().err()?;
+    Some(Message::Error(format!("can't save the session: {}", error)))
+}
+
+fn main() {
+    let options = Options::parse(std::env::args().skip(1)).unwrap_or_else(|e| cli_error(&e));
+    if let Some(path) = &options.batch {
+        batch(&options, path);
+    }
+    let (problems, imported) = select_problems(&options);
+    let mut session = load_session(&options);
+
+    let mut repr_conf = ReprConf{
+        negation: true,
+        formated: options.color,
+        unicode: options.unicode,
+        emphazis: false,
+        numbered: false,
+    };
+
+    let mut records = Vec::new();
+    let mut library = load_library(&options);
+    import_lemmas(&mut library, &imported);
+    let mut statuses: Vec<Option<Status>> = problems
+        .iter()
+        .map(|(key, p)| session.as_ref().and_then(|s| s.get(key, &p.sequent)).map(|(status, _)| status))
+        .collect();
+    let screen = Screen::enter(options.color);
+    let mut editor = Editor::new(screen.is_raw());
+    let mut overlay = Some(Overlay::new(" help, :h to show it again", usage(repr_conf.formated)));
+    let mut message = None;
+    // input left on the command line after a parse error
+    let mut initial = String::new();
+    for (n, (key, problem)) in problems.iter().enumerate() {
+        let mut tree = UndoTree::new(
+            Proof::not_proven(problem.sequent.clone()),
+            Script::new(problem.sequent.clone()),
+        );
+        let context = Context {
+            lemmas: library.lemmas(),
+            definitions: &problem.definitions,
+        };
+        let saved = session.as_ref().and_then(|s| s.get(key, &problem.sequent));
+        if let Some((status, saved)) = saved {
+            if status == Status::Solved {
+                continue;
+            }
+            if let Some(restored) = UndoTree::from_script(saved, context) {
+                tree = restored;
+            }
+        }
+        let mut focus: Option<GoalPath> = None;
+        let mut view = Viewport::default();
+        let mut record = Record::new(key, problem);
+        let mut quit = false;
+        let mut skipped = false;
+        'problem: loop {
+            while !tree.proof().is_complete() {
+                let p = tree.proof();
+                let goals = p.open_goals();
+                let target = focus
+                    .clone()
+                    .filter(|f| goals.contains(f))
+                    .unwrap_or_else(|| goals[0].clone());
+                let index = goals.iter().position(|g| g == &target);
+                let goal = p.goal(&target).map(Proof::sequent);
+                let list = problem_list(&problems, &statuses, n, repr_conf.formated);
+                let size = screen.size();
+                let (rows, cols) = screen.main_size(size, &list);
+                let main = match &mut overlay {
+                    Some(overlay) => overlay.lines(rows, repr_conf.formated),
+                    None => {
+                        let mut below = goal_list(p, &goals, &target, repr_conf);
+                        below.extend(goal.map(|g| goal_panel(g, repr_conf)).unwrap_or_default());
+                        // sequents wider than the pane are broken on several lines
+                        let layout = p.repr_conf(repr_conf).max_width(cols).layout();
+                        let tree_rows = rows.saturating_sub(below.len() + 1).max(5);
+                        let mut lines = view.lines(&layout, index, tree_rows, cols, repr_conf.formated);
+                        lines.push(String::new());
+                        lines.extend(below);
+                        lines
+                    }
+                };
+                screen.draw(size, &Frame {
+                    title: problem_title(key, problem),
+                    problems: list,
+                    main,
+                    message: message.take(),
+                });
+                let context = Context {
+                    lemmas: library.lemmas(),
+                    definitions: &problem.definitions,
+                };
+                let input = match user_input(&mut editor, &std::mem::take(&mut initial), goal, context) {
+                    Some(input) => input,
+                    None => {
+                        quit = true;
+                        break 'problem;
+                    }
+                };
+                let command = input.parse::<Command>();
+                if let (Some(overlay), Ok(Command::Pan(direction, n))) = (&mut overlay, &command) {
+                    scroll(overlay, direction, *n, rows);
+                    continue;
+                }
+                overlay = None;
+                if input.is_empty() {
+                    continue;
+                }
+                match command {
+                    Ok(Command::Skip) => {
+                        statuses[n] = Some(Status::Skipped);
+                        message = save_session(&mut session, key, Status::Skipped, tree.script());
+                        skipped = true;
+                        break 'problem;
+                    }
+                    Ok(Command::Back) => {
+                        focus = None;
+                        view.center();
+                        record.undo();
+                        tree.back();
+                    }
+                    Ok(Command::Restart) => {
+                        focus = None;
+                        view.center();
+                        tree.restart();
+                    }
+                    Ok(Command::Tree) => {
+                        overlay = Some(Overlay::new(" explored steps, :goto <S> to go back to one", tree.lines()));
+                    }
+                    Ok(Command::Lemma(_)) => message = Some(Message::Error("not solved yet".to_owned())),
+                    Ok(Command::Lemmas) => {
+                        overlay = Some(Overlay::new(" lemmas, use <NAME> to apply one", lemma_lines(&library, repr_conf)));
+                    }
+                    Ok(Command::Goto(target)) => {
+                        focus = None;
+                        view.center();
+                        if !tree.goto(&target) {
+                            message = Some(Message::Error(format!("no such state `{}`", target)));
+                        }
+                    }
+                    Ok(Command::Mark(name)) => {
+                        tree.mark(&name);
+                        message = Some(Message::Info(format!("state marked `{}`", name)));
+                    }
+                    Ok(Command::Pan(direction, n)) => view.pan(direction, n, (rows, cols)),
+                    Ok(Command::Center) => view.center(),
+                    Ok(Command::Help) => {
+                        overlay = Some(Overlay::new(" help", usage(repr_conf.formated)));
+                    }
+                    Ok(Command::Negation) => {
+                        repr_conf.negation = !repr_conf.negation;
+                    }
+                    Ok(Command::Hint) => match &problem.hint {
+                        Some(hint) => {
+                            overlay = Some(Overlay::new(" hint", hint.lines().map(str::to_owned).collect()));
+                        }
+                        None => message = Some(Message::Error("no hint for this problem".to_owned())),
+                    },
+                    Ok(Command::Focus(n)) => match n.checked_sub(1).and_then(|i| goals.get(i)) {
+                        Some(goal) => {
+                            focus = Some(goal.clone());
+                            view.center();
+                        }
+                        None => message = Some(Message::Error(format!("no goal number {}", n))),
+                    },
+                    Ok(Command::Write(path)) => {
+                        message = Some(write_script(tree.script(), &path));
+                    }
+                    Ok(Command::Quit) => {
+                        quit = true;
+                        break 'problem;
+                    }
+                    Ok(Command::Rule(rule)) => {
+                        let mut p = p.clone();
+                        if !problem.allows(&rule) {
+                            record.failure();
+                            message = Some(Message::Error(format!("`{}`: rule not allowed", rule)));
+                        } else if !problem.allows_with(&rule, context) {
+                            record.failure();
+                            message = Some(Message::Error(format!("`{}`: the lemma uses rules not allowed", rule)));
+                        } else if !p.prove_at_with(&target, rule.clone(), context) {
+                            record.failure();
+                            let error = match &rule {
+                                ProveBy::Use(name, _) if context.lemma(name).is_none() => "unknown lemma",
+                                ProveBy::Unfold(name, _) | ProveBy::Fold(name, _)
+                                    if context.definition(name).is_none() =>
+                                {
+                                    "unknown definition"
+                                }
+                                _ => "can't apply rule",
+                            };
+                            message = Some(Message::Error(format!("`{}`: {}", rule, error)));
+                        } else {
+                            record.rule();
+                            let mut script = tree.script().clone();
+                            if target == goals[0] {
+                                script.push(rule);
+                            } else {
+                                script.push_at(target.clone(), rule);
+                            }
+                            // keeps working on the proof of the focused sequent
+                            focus = p.open_goals().into_iter().find(|g| g.starts_with(&target));
+                            view.center();
+                            tree.push(p, script);
+                        }
+                    }
+                    Err(e) => {
+                        message = Some(parse_error(&e, &input));
+                        initial = input;
+                    }
+                }
+                let status = if tree.proof().is_complete() {
+                    Status::Solved
+                } else {
+                    Status::InProgress
+                };
+                statuses[n] = Some(status);
+                if let Some(error) = save_session(&mut session, key, status, tree.script()) {
+                    message = Some(error);
+                }
+            }
+            record.solved();
+            view.center();
+            loop {
+                let list = problem_list(&problems, &statuses, n, repr_conf.formated);
+                let size = screen.size();
+                let (rows, cols) = screen.main_size(size, &list);
+                let main = match &mut overlay {
+                    Some(overlay) => overlay.lines(rows, repr_conf.formated),
+                    None => {
+                        let layout = tree.proof().repr_conf(repr_conf).max_width(cols).layout();
+                        view.lines(&layout, None, rows, cols, repr_conf.formated)
+                    }
+                };
+                let solved = "solved, enter for the next problem, :w <FILE> or :lemma <NAME>";
+                screen.draw(size, &Frame {
+                    title: problem_title(key, problem),
+                    problems: list,
+                    main,
+                    message: Some(message.take().unwrap_or_else(|| Message::Info(solved.to_owned()))),
+                });
+                let context = Context {
+                    lemmas: library.lemmas(),
+                    definitions: &problem.definitions,
+                };
+                let input = match user_input(&mut editor, &std::mem::take(&mut initial), None, context) {
+                    Some(input) => input,
+                    None => {
+                        quit = true;
+                        break 'problem;
+                    }
+                };
+                let command = input.parse::<Command>();
+                if let (Some(overlay), Ok(Command::Pan(direction, n))) = (&mut overlay, &command) {
+                    scroll(overlay, direction, *n, rows);
+                    continue;
+                }
+                overlay = None;
+                match command {
+                    Ok(Command::Write(path)) => message = Some(write_script(tree.script(), &path)),
+                    Ok(Command::Quit) => {
+                        quit = true;
+                        break 'problem;
+                    }
+                    // keeps exploring other proofs
+                    Ok(Command::Back) => {
+                        record.undo();
+                        tree.back();
+                        break;
+                    }
+                    Ok(Command::Restart) => {
+                        tree.restart();
+                        break;
+                    }
+                    Ok(Command::Goto(target)) if tree.goto(&target) => break,
+                    Ok(Command::Goto(target)) => {
+                        message = Some(Message::Error(format!("no such state `{}`", target)));
+                    }
+                    Ok(Command::Tree) => {
+                        overlay = Some(Overlay::new(" explored steps, :goto <S> to go back to one", tree.lines()));
+                    }
+                    Ok(Command::Mark(name)) => {
+                        tree.mark(&name);
+                        message = Some(Message::Info(format!("state marked `{}`", name)));
+                    }
+                    Ok(Command::Lemma(name)) => {
+                        message = Some(match library.add(&name, tree.proof().clone(), tree.script()) {
+                            Ok(()) => match library.save() {
+                                Ok(()) => Message::Info(format!("lemma `{}` saved", name)),
+                                Err(e) => Message::Error(format!("can't save the lemma: {}", e)),
+                            },
+                            Err(e) => Message::Error(format!("can't add the lemma: {}", e)),
+                        });
+                    }
+                    Ok(Command::Lemmas) => {
+                        overlay = Some(Overlay::new(" lemmas, use <NAME> to apply one", lemma_lines(&library, repr_conf)));
+                    }
+                    Ok(Command::Pan(direction, n)) => view.pan(direction, n, (rows, cols)),
+                    Ok(Command::Center) => view.center(),
+                    Err(e) if !input.is_empty() => {
+                        message = Some(parse_error(&e, &input));
+                        initial = input;
+                    }
+                    _ => break 'problem,
+                }
+            }
+        }
+        if options.exercise {
+            let status = match (tree.proof().is_complete(), skipped) {
+                (true, _) => Status::Solved,
+                (false, true) => Status::Skipped,
+                (false, false) => Status::InProgress,
+            };
+            records.push(record.finish(status, problem, tree.proof()));
+        }
+        if quit {
+            break;
+        }
+    }
+    drop(screen);
+    records.iter_mut().for_each(Record::wait);
+    if options.exercise {
+        print!("{}", exercise::summary(&records));
+    }
+    if let Some(path) = &options.report {
+        if let Err(e) = std::fs::write(path, exercise::report(&records)) {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(2);
+        }
+    }
+}
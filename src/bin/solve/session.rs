@@ -0,0 +1,132 @@
+use prove::{ParseError, Script, Sequent};
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    Skipped,
+    InProgress,
+}
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solved => "solved",
+            Self::Skipped => "skipped",
+            Self::InProgress => "in-progress",
+        }
+        .fmt(f)
+    }
+}
+
+/// Progress on the problems of a problem file, saved next to it in
+/// `<FILE>.session`, eg:
+///
+/// ```text
+/// problem contrapose in-progress
+/// ~P->~Q |- ~~Q->~~P
+/// ii
+/// problem 2 solved
+/// P |- P
+/// h
+/// ```
+///
+/// Problems are identified by their name, or by their index from 1 when they
+/// have none, followed by the script of their proof.
+pub struct Session {
+    path: PathBuf,
+    entries: Vec<(String, Status, Script)>,
+}
+
+/// Error in a session file, `line` starts at 1 and `source` is the content
+/// of the file
+pub struct SessionError {
+    pub path: PathBuf,
+    pub source: String,
+    pub line: usize,
+    pub error: ParseError,
+}
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.path.display(), self.line, self.error)
+    }
+}
+
+impl Session {
+    /// Session of the problem file at `path`, empty if it was never saved
+    pub fn load(path: &str) -> Result<Self, Box<SessionError>> {
+        let path = PathBuf::from(format!("{}.session", path));
+        let mut entries = Vec::new();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Ok(Self { path, entries }),
+        };
+        let error = |line: usize, error: ParseError| {
+            Box::new(SessionError {
+                path: path.clone(),
+                source: content.clone(),
+                line,
+                error,
+            })
+        };
+        let mut headers = Vec::new();
+        let mut at = 0;
+        for (i, line) in content.split_inclusive('\n').enumerate() {
+            if let Some(header) = line.strip_prefix("problem ") {
+                let header = header.trim();
+                let status = match header.rsplit_once(' ') {
+                    Some((key, "solved")) => Some((key, Status::Solved)),
+                    Some((key, "skipped")) => Some((key, Status::Skipped)),
+                    Some((key, "in-progress")) => Some((key, Status::InProgress)),
+                    _ => None,
+                };
+                let (key, status) = status.ok_or_else(|| {
+                    let start = at + "problem ".len();
+                    error(
+                        i + 1,
+                        ParseError::new(
+                            start..start + header.len(),
+                            "invalid problem header, the name or index of the problem must be followed by its status",
+                        )
+                        .expected(&["solved", "skipped", "in-progress"])
+                            .found(header),
+                    )
+                })?;
+                headers.push((i, at, at + line.len(), key.to_owned(), status));
+            }
+            at += line.len();
+        }
+        for (n, (line, _, start, key, status)) in headers.iter().enumerate() {
+            let end = headers.get(n + 1).map(|h| h.1).unwrap_or(content.len());
+            let script: Script = content[*start..end].parse().map_err(|e: prove::ScriptError| {
+                error(line + 1 + e.line, e.error.offset(*start))
+            })?;
+            entries.push((key.clone(), *status, script));
+        }
+        Ok(Self { path, entries })
+    }
+
+    /// Status and script of the problem, if its sequent didn't change since
+    /// it was saved
+    pub fn get(&self, key: &str, sequent: &Sequent) -> Option<(Status, &Script)> {
+        self.entries
+            .iter()
+            .find(|(k, _, script)| k == key && script.sequent() == sequent)
+            .map(|(_, status, script)| (*status, script))
+    }
+
+    pub fn set(&mut self, key: &str, status: Status, script: &Script) {
+        match self.entries.iter_mut().find(|(k, _, _)| k == key) {
+            Some(entry) => *entry = (key.to_owned(), status, script.clone()),
+            None => self.entries.push((key.to_owned(), status, script.clone())),
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut content = String::new();
+        for (key, status, script) in &self.entries {
+            content += &format!("problem {} {}\n{}", key, status, script);
+        }
+        std::fs::write(&self.path, content)
+    }
+}
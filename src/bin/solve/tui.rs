@@ -0,0 +1,203 @@
+use crate::editor::RawMode;
+use crate::viewport::{columns, terminal_size};
+use std::io::{IsTerminal, Write};
+
+/// Rows and columns of the screen when the output is not a terminal
+const DEFAULT_SIZE: (usize, usize) = (24, 80);
+
+/// Rows taken by the title bar, the message and the command line
+const BARS: usize = 3;
+
+/// Text shown above the command line until the next command
+pub enum Message {
+    Info(String),
+    Error(String),
+}
+
+/// Lines shown in the main pane instead of the proof, like the help or the
+/// tree of the explored steps, until the next command other than `:up` and
+/// `:down`, which scroll them
+pub struct Overlay {
+    title: String,
+    lines: Vec<String>,
+    scroll: usize,
+}
+impl Overlay {
+    pub fn new(title: &str, lines: Vec<String>) -> Self {
+        Self {
+            title: title.to_owned(),
+            lines,
+            scroll: 0,
+        }
+    }
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll += n;
+    }
+    /// Lines that fit in `rows`, below the title
+    pub fn lines(&mut self, rows: usize, color: bool) -> Vec<String> {
+        let rows = rows.saturating_sub(2).max(1);
+        let clipped = self.lines.len() > rows;
+        let inner = if clipped { rows - 1 } else { rows };
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(inner));
+        let mut lines = vec![style(&self.title, "\x1b[7m", color), String::new()];
+        lines.extend(self.lines.iter().skip(self.scroll).take(inner).cloned());
+        let below = self.lines.len().saturating_sub(self.scroll + inner);
+        if below > 0 {
+            let more = format!("  ▼ {} more lines below, :down to see them", below);
+            lines.push(style(&more, "\x1b[2m", color));
+        }
+        lines
+    }
+}
+
+fn style(s: &str, escape: &str, color: bool) -> String {
+    if color {
+        format!("{}{}\x1b[0m", escape, s)
+    } else {
+        s.to_owned()
+    }
+}
+
+/// Columns taken by a line holding formating escape sequences
+pub fn width(line: &str) -> usize {
+    let mut width = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// The line cropped or padded with spaces to `cols` columns
+fn fit(line: &str, cols: usize, color: bool) -> String {
+    let (visible, _) = columns(line, 0, cols);
+    let pad = cols.saturating_sub(width(&visible));
+    let reset = if color { "\x1b[0m" } else { "" };
+    format!("{}{}{}", visible, reset, " ".repeat(pad))
+}
+
+/// Plain text cropped or padded with spaces to `cols` columns
+fn bar(text: &str, cols: usize) -> String {
+    let text: String = text.chars().take(cols).collect();
+    format!("{:<cols$}", text, cols = cols)
+}
+
+/// Content of the screen, from top to bottom: a title bar, the list of the
+/// problems beside the main pane, and a message above the command line
+pub struct Frame {
+    pub title: String,
+    pub problems: Vec<String>,
+    pub main: Vec<String>,
+    pub message: Option<Message>,
+}
+
+/// Full-screen interface of `solve`, on the alternate screen of the
+/// terminal in raw mode until dropped, the frames are printed one after the
+/// other when the output is not a terminal
+pub struct Screen {
+    terminal: bool,
+    color: bool,
+    raw: Option<RawMode>,
+}
+impl Screen {
+    pub fn enter(color: bool) -> Self {
+        let terminal = std::io::stdout().is_terminal();
+        let raw = if terminal && std::io::stdin().is_terminal() {
+            RawMode::enable()
+        } else {
+            None
+        };
+        if terminal {
+            print!("\x1b[?1049h");
+        }
+        Self { terminal, color, raw }
+    }
+    /// Whether the terminal is in raw mode, for the line editing
+    pub fn is_raw(&self) -> bool {
+        self.raw.is_some()
+    }
+    /// Rows and columns of the screen, asked to the terminal, so once per
+    /// frame, the size is then given to the other methods
+    pub fn size(&self) -> (usize, usize) {
+        terminal_size().unwrap_or(DEFAULT_SIZE)
+    }
+    /// Columns of the list of the problems, none for a single problem
+    fn list_width(cols: usize, problems: &[String]) -> usize {
+        if problems.len() < 2 {
+            return 0;
+        }
+        let widest = problems.iter().map(|p| width(p)).max().unwrap_or(0);
+        (widest + 1).min(cols / 4)
+    }
+    /// Rows and columns of the main pane, beside the list of `problems`, on
+    /// a screen of `size`
+    pub fn main_size(&self, size: (usize, usize), problems: &[String]) -> (usize, usize) {
+        let (rows, cols) = size;
+        let list = Self::list_width(cols, problems);
+        let separator = if list > 0 { 2 } else { 0 };
+        (rows.saturating_sub(BARS).max(1), cols.saturating_sub(list + separator).max(1))
+    }
+    /// Draws the frame on a screen of `size`, and leaves the cursor on the
+    /// command line
+    pub fn draw(&self, size: (usize, usize), frame: &Frame) {
+        let color = self.color;
+        let (rows, cols) = size;
+        let list = Self::list_width(cols, &frame.problems);
+        let (height, main) = self.main_size(size, &frame.problems);
+        // the frames are only as high as their content out of a terminal
+        let height = if self.terminal {
+            height
+        } else {
+            frame.problems.len().max(frame.main.len()).min(height)
+        };
+        let mut rows_out = Vec::with_capacity(rows);
+        rows_out.push(style(&bar(&frame.title, cols), "\x1b[7m", color));
+        for i in 0..height {
+            let right = frame.main.get(i).map_or("", String::as_str);
+            if list > 0 {
+                let left = frame.problems.get(i).map_or("", String::as_str);
+                let separator = style("│", "\x1b[2m", color);
+                rows_out.push(format!("{}{} {}", fit(left, list, color), separator, fit(right, main, color)));
+            } else {
+                rows_out.push(fit(right, main, color));
+            }
+        }
+        rows_out.push(match &frame.message {
+            Some(Message::Info(text)) => style(&bar(text, cols), "\x1b[94m", color),
+            Some(Message::Error(text)) => style(&bar(text, cols), "\x1b[91m", color),
+            None => String::new(),
+        });
+        let mut out = String::new();
+        if self.terminal {
+            out.push_str("\x1b[H");
+            for row in &rows_out {
+                out.push_str(row);
+                out.push_str("\x1b[K\r\n");
+            }
+            out.push_str("\x1b[J");
+        } else {
+            for row in &rows_out {
+                out.push_str(row.trim_end());
+                out.push('\n');
+            }
+        }
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if self.terminal {
+            print!("\x1b[?1049l");
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
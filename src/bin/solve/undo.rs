@@ -0,0 +1,144 @@
+use prove::{Context, Proof, Script};
+
+struct State {
+    proof: Proof,
+    script: Script,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Every state of a proof the user went through, the state after a rule is
+/// a child of the state before it, so undone branches are never lost
+pub struct UndoTree {
+    states: Vec<State>,
+    current: usize,
+    marks: Vec<(String, usize)>,
+}
+impl UndoTree {
+    pub fn new(proof: Proof, script: Script) -> Self {
+        Self {
+            states: vec![State {
+                proof,
+                script,
+                parent: None,
+                children: Vec::new(),
+            }],
+            current: 0,
+            marks: Vec::new(),
+        }
+    }
+    /// Tree with a single branch made of the steps of the script, `None` if
+    /// a step can't be applied
+    pub fn from_script(script: &Script, context: Context<'_>) -> Option<Self> {
+        let mut proof = Proof::not_proven(script.sequent().clone());
+        let mut tree = Self::new(proof.clone(), Script::new(script.sequent().clone()));
+        let mut partial = Script::new(script.sequent().clone());
+        for step in script.steps() {
+            if !step.apply_with(&mut proof, context) {
+                return None;
+            }
+            match &step.goal {
+                Some(goal) => partial.push_at(goal.clone(), step.rule.clone()),
+                None => partial.push(step.rule.clone()),
+            }
+            tree.push(proof.clone(), partial.clone());
+        }
+        Some(tree)
+    }
+    pub fn proof(&self) -> &Proof {
+        &self.states[self.current].proof
+    }
+    pub fn script(&self) -> &Script {
+        &self.states[self.current].script
+    }
+    /// Adds a state after the current one, or moves to the existing one if
+    /// the same step was already explored
+    pub fn push(&mut self, proof: Proof, script: Script) {
+        let existing = self.states[self.current]
+            .children
+            .iter()
+            .copied()
+            .find(|i| self.states[*i].script.to_string() == script.to_string());
+        self.current = existing.unwrap_or_else(|| {
+            let id = self.states.len();
+            self.states.push(State {
+                proof,
+                script,
+                parent: Some(self.current),
+                children: Vec::new(),
+            });
+            self.states[self.current].children.push(id);
+            id
+        });
+    }
+    /// Moves to the state before the current one
+    pub fn back(&mut self) -> bool {
+        match self.states[self.current].parent {
+            Some(parent) => {
+                self.current = parent;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Moves to the state without any rule applied
+    pub fn restart(&mut self) {
+        self.current = 0;
+    }
+    /// Moves to the state with the number shown by `lines`, or to a mark
+    pub fn goto(&mut self, target: &str) -> bool {
+        let id = match target.parse::<usize>() {
+            Ok(id) if id < self.states.len() => id,
+            Ok(_) => return false,
+            Err(_) => match self.marks.iter().find(|(name, _)| name == target) {
+                Some((_, id)) => *id,
+                None => return false,
+            },
+        };
+        self.current = id;
+        true
+    }
+    /// Names the current state, a name already used moves to it
+    pub fn mark(&mut self, name: &str) {
+        self.marks.retain(|(n, _)| n != name);
+        self.marks.push((name.to_owned(), self.current));
+    }
+    /// The states drawn as a tree, with the step leading to each of them
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        // each state is drawn after `prefix` and `head`, which is the branch
+        // leading to it when its parent has several children
+        let mut stack = vec![(0, String::new(), "")];
+        while let Some((id, prefix, head)) = stack.pop() {
+            let state = &self.states[id];
+            let label = match state.script.steps().last() {
+                Some(step) if id != 0 => step.to_string(),
+                _ => "start".to_owned(),
+            };
+            let current = if id == self.current { ">" } else { " " };
+            let solved = if state.proof.is_complete() { " (solved)" } else { "" };
+            let marks: String = self
+                .marks
+                .iter()
+                .filter(|(_, i)| *i == id)
+                .map(|(name, _)| format!(" [{}]", name))
+                .collect();
+            lines.push(format!("{} {:<4}{}{}{}{}{}", current, id, prefix, head, label, solved, marks));
+            let prefix = match head {
+                "├ " => prefix + "│ ",
+                "└ " => prefix + "  ",
+                _ => prefix,
+            };
+            let n = state.children.len();
+            for (i, child) in state.children.iter().enumerate().rev() {
+                let head = match (n, i + 1 == n) {
+                    (1, _) => "",
+                    (_, true) => "└ ",
+                    _ => "├ ",
+                };
+                stack.push((*child, prefix.clone(), head));
+            }
+        }
+        lines
+    }
+}
@@ -0,0 +1,140 @@
+use prove::ProofLayout;
+use std::io::IsTerminal;
+use std::process::{Command, Stdio};
+
+/// Rows and columns of the terminal, `None` if the output is not a terminal
+pub fn terminal_size() -> Option<(usize, usize)> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    let (rows, cols) = output.trim().split_once(' ')?;
+    let size = (rows.parse().ok()?, cols.parse().ok()?);
+    // unknown size of some pseudo terminals
+    Some(size).filter(|&(rows, cols)| rows > 0 && cols > 0)
+}
+
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Columns `from..to` of a line holding formating escape sequences, which
+/// are all kept, and whether they hold something else than spaces
+pub fn columns(line: &str, from: usize, to: usize) -> (String, bool) {
+    let mut out = String::new();
+    let mut content = false;
+    let mut column = 0;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            for c in chars.by_ref() {
+                out.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        if (from..to).contains(&column) {
+            out.push(c);
+            content |= c != ' ';
+        }
+        column += 1;
+    }
+    (out, content)
+}
+
+/// Part of a proof tree shown in the terminal, centred on a goal and moved
+/// by the user
+#[derive(Default)]
+pub struct Viewport {
+    x: isize,
+    y: isize,
+}
+impl Viewport {
+    /// Moves the view by `n` columns or lines, half a view of `rows` lines
+    /// and `cols` columns by default
+    pub fn pan(&mut self, direction: Direction, n: Option<usize>, (rows, cols): (usize, usize)) {
+        match direction {
+            Direction::Left => self.x -= n.unwrap_or(cols / 2) as isize,
+            Direction::Right => self.x += n.unwrap_or(cols / 2) as isize,
+            Direction::Up => self.y -= n.unwrap_or(rows / 4) as isize,
+            Direction::Down => self.y += n.unwrap_or(rows / 4) as isize,
+        }
+    }
+    /// Centres the view back on the goal
+    pub fn center(&mut self) {
+        *self = Self::default();
+    }
+    /// Lines of the part of the proof around the `goal`th open goal, or
+    /// around the conclusion, that fits in `rows` lines and `cols` columns,
+    /// markers show where the proof is clipped
+    pub fn lines(
+        &mut self,
+        layout: &ProofLayout,
+        goal: Option<usize>,
+        rows: usize,
+        cols: usize,
+        color: bool,
+    ) -> Vec<String> {
+        let dim = |s: &str| if color { format!("\x1b[2m{}\x1b[0m", s) } else { s.to_owned() };
+        let height = layout.lines.len();
+        if layout.width <= cols && height <= rows {
+            return layout.lines.clone();
+        }
+        let mut lines = Vec::new();
+        let clip_x = layout.width > cols;
+        let clip_y = height > rows;
+        let inner_w = if clip_x { cols.saturating_sub(2).max(1) } else { layout.width };
+        let inner_h = if clip_y { rows.saturating_sub(2).max(1) } else { height };
+        let (gx, gy, gw) = goal
+            .and_then(|i| layout.goals.get(i).copied())
+            .unwrap_or((0, height - 1, layout.width));
+        let clamp = |v: isize, max: usize| v.clamp(0, max as isize);
+        let x = clamp((gx + gw / 2) as isize - (inner_w / 2) as isize, layout.width - inner_w);
+        let y = clamp(gy as isize - (inner_h / 2) as isize, height - inner_h);
+        // moving past the borders is forgotten, to move back at once
+        self.x = clamp(x + self.x, layout.width - inner_w) - x;
+        self.y = clamp(y + self.y, height - inner_h) - y;
+        let (x0, y0) = ((x + self.x) as usize, (y + self.y) as usize);
+        if clip_y {
+            lines.push(match y0 {
+                0 => String::new(),
+                n => dim(&format!("  ▲ {} more lines above, :up to see them", n)),
+            });
+        }
+        for line in &layout.lines[y0..y0 + inner_h] {
+            let (visible, _) = columns(line, x0, x0 + inner_w);
+            if clip_x {
+                let left = if columns(line, 0, x0).1 { dim("<") } else { " ".to_owned() };
+                let right = if columns(line, x0 + inner_w, usize::MAX).1 {
+                    dim(">")
+                } else {
+                    " ".to_owned()
+                };
+                // the escape sequences of the hidden part may leave a format on
+                let reset = if color { "\x1b[0m" } else { "" };
+                lines.push(format!("{}{}{}{}", left, visible, reset, right));
+            } else {
+                lines.push(visible);
+            }
+        }
+        if clip_y {
+            lines.push(match height - y0 - inner_h {
+                0 => String::new(),
+                n => dim(&format!("  ▼ {} more lines below, :down to see them", n)),
+            });
+        }
+        lines
+    }
+}
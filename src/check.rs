@@ -0,0 +1,336 @@
+use super::definition::Definition;
+use super::proof::{Context, GoalPath, Proof, Rule};
+use super::property::Prop;
+use super::sequent::Sequent;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error found by the kernel, at the path of the invalid node from the root
+/// of the proof
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckError {
+    pub path: GoalPath,
+    pub message: &'static str,
+}
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Trusted kernel: checks, independently of the way the proof was built,
+/// that the proof is complete and that every rule application is
+/// valid, that is the premises are exactly what the rule requires, and the
+/// proofs of the lemmas are checked too, proofs unfolding or folding
+/// definitions are rejected, see `check_with`
+pub fn check(proof: &Proof) -> Result<(), CheckError> {
+    check_with(proof, Context::default())
+}
+
+/// Same as `check`, the definitions unfolded or folded by the proof must be
+/// the ones of the context, and not only the ones the proof carries
+pub fn check_with(proof: &Proof, context: Context<'_>) -> Result<(), CheckError> {
+    check_at(proof, &GoalPath::root(), context)
+}
+
+fn check_at(proof: &Proof, path: &GoalPath, context: Context<'_>) -> Result<(), CheckError> {
+    let error = |message| CheckError {
+        path: path.clone(),
+        message,
+    };
+    let rule = proof.rule().ok_or_else(|| error("sequent not proven"))?;
+    check_rule(proof.sequent(), rule, context).map_err(error)?;
+    for (i, p) in rule.hypotheses().iter().enumerate() {
+        check_at(p, &path.child(i), context)?;
+    }
+    Ok(())
+}
+
+fn expect(p: &Proof, hypotheses: &[Prop], conclusion: &Prop) -> Result<(), &'static str> {
+    if p.sequent().hypotheses() != hypotheses {
+        Err("premise hypotheses don't match the rule")
+    } else if p.sequent().conclusion() != conclusion {
+        Err("premise conclusion doesn't match the rule")
+    } else {
+        Ok(())
+    }
+}
+
+fn with(h: &[Prop], extra: &[&Prop]) -> Vec<Prop> {
+    h.iter().chain(extra.iter().copied()).cloned().collect()
+}
+
+/// Finds a hypothesis that, once replaced, gives the hypotheses of the premises
+fn expect_replaced(
+    s: &Sequent,
+    premises: &[Proof],
+    replace: impl Fn(&Prop) -> Option<Vec<Vec<Prop>>>,
+) -> Result<(), &'static str> {
+    let h = s.hypotheses();
+    let found = h.iter().enumerate().any(|(i, p)| match replace(p) {
+        Some(alternatives) => premises.iter().zip(alternatives).all(|(p, with)| {
+            let hypotheses: Vec<Prop> = h[..i]
+                .iter()
+                .cloned()
+                .chain(with)
+                .chain(h[i + 1..].iter().cloned())
+                .collect();
+            expect(p, &hypotheses, s.conclusion()).is_ok()
+        }),
+        None => false,
+    });
+    if found {
+        Ok(())
+    } else {
+        Err("no hypothesis matches the rule")
+    }
+}
+
+/// Whether the premise is the sequent with every application of the
+/// connective, or every instance of its body, rewritten in a single formula:
+/// the conclusion or one of the hypotheses
+fn expect_rewritten(s: &Sequent, p: &Proof, rewrite: impl Fn(&Prop) -> Prop) -> Result<(), &'static str> {
+    let premise = p.sequent();
+    let before: Vec<&Prop> = s.hypotheses().iter().chain(Some(s.conclusion())).collect();
+    let after: Vec<&Prop> = premise.hypotheses().iter().chain(Some(premise.conclusion())).collect();
+    let changed: Vec<usize> = (0..before.len()).filter(|&i| after.get(i) != Some(&before[i])).collect();
+    match changed[..] {
+        [i] if before.len() == after.len() && &rewrite(before[i]) == after[i] => Ok(()),
+        _ => Err("premise is not the sequent with the definition rewritten in one formula"),
+    }
+}
+
+fn known(definition: &Definition, context: Context<'_>) -> Result<(), &'static str> {
+    match context.definition(definition.name()) {
+        Some(known) if known == definition => Ok(()),
+        Some(_) => Err("definition differs from the one of the context"),
+        None => Err("unknown definition"),
+    }
+}
+
+pub(crate) fn check_rule(s: &Sequent, rule: &Rule, context: Context<'_>) -> Result<(), &'static str> {
+    let (h, c) = (s.hypotheses(), s.conclusion());
+    match rule {
+        Rule::Hypothesis([]) => {
+            if h.contains(c) {
+                Ok(())
+            } else {
+                Err("conclusion is not a hypothesis")
+            }
+        }
+        Rule::ImplicationIntroduction([p]) => match c {
+            Prop::Implication(a, b) => expect(p, &with(h, &[a.as_ref()]), b),
+            _ => Err("conclusion is not an implication"),
+        },
+        Rule::ImplicationIntroductions([p]) => {
+            let mut hypotheses = h.to_owned();
+            let mut conclusion = c;
+            while let Prop::Implication(a, b) = conclusion {
+                hypotheses.push(a.as_ref().clone());
+                conclusion = b;
+            }
+            if hypotheses.len() == h.len() {
+                Err("conclusion is not an implication")
+            } else {
+                expect(p, &hypotheses, conclusion)
+            }
+        }
+        Rule::ModusPonens([ab, a]) => {
+            let a_prop = a.sequent().conclusion();
+            expect(a, h, a_prop)?;
+            expect(ab, h, &a_prop.implies(c))
+        }
+        Rule::WeakModusPonens([a]) => {
+            expect(a, h, a.sequent().conclusion())?;
+            if h.contains(&a.sequent().conclusion().implies(c)) {
+                Ok(())
+            } else {
+                Err("no hypothesis matches the rule")
+            }
+        }
+        Rule::DisjonctionIntroductionLeft([p]) => match c {
+            Prop::Disjonction(a, _) => expect(p, h, a),
+            _ => Err("conclusion is not a disjonction"),
+        },
+        Rule::DisjonctionIntroductionRight([p]) => match c {
+            Prop::Disjonction(_, b) => expect(p, h, b),
+            _ => Err("conclusion is not a disjonction"),
+        },
+        Rule::DisjonctionElimination([ab, pa, pb]) => match ab.sequent().conclusion() {
+            Prop::Disjonction(a, b) => {
+                expect(ab, h, ab.sequent().conclusion())?;
+                expect(pa, &with(h, &[a.as_ref()]), c)?;
+                expect(pb, &with(h, &[b.as_ref()]), c)
+            }
+            _ => Err("first premise is not a disjonction"),
+        },
+        Rule::DisjonctionWeakElimination(premises) => {
+            expect_replaced(s, premises, |p| match p {
+                Prop::Disjonction(a, b) => Some(vec![
+                    vec![a.as_ref().clone()],
+                    vec![b.as_ref().clone()],
+                ]),
+                _ => None,
+            })
+        }
+        Rule::Exfalso([p]) => expect(p, h, &Prop::False),
+        Rule::ReductioAdAbsurdum([p]) => expect(p, &with(h, &[&c.negate()]), &Prop::False),
+        Rule::EquivalenceIntroduction([ab, ba]) => match c {
+            Prop::Equivalence(a, b) => {
+                expect(ab, h, &a.implies(b))?;
+                expect(ba, h, &b.implies(a))
+            }
+            _ => Err("conclusion is not an equivalence"),
+        },
+        Rule::EquivalenceWeakElimination(premises) => {
+            expect_replaced(s, premises, |p| match p {
+                Prop::Equivalence(a, b) => Some(vec![vec![a.implies(b), b.implies(a)]]),
+                _ => None,
+            })
+        }
+        Rule::ConjonctionIntroduction([pa, pb]) => match c {
+            Prop::Conjonction(a, b) => {
+                expect(pa, h, a)?;
+                expect(pb, h, b)
+            }
+            _ => Err("conclusion is not a conjonction"),
+        },
+        Rule::ConjonctionElimination([ab, p]) => match ab.sequent().conclusion() {
+            Prop::Conjonction(a, b) => {
+                expect(ab, h, ab.sequent().conclusion())?;
+                expect(p, &with(h, &[a.as_ref(), b.as_ref()]), c)
+            }
+            _ => Err("first premise is not a conjonction"),
+        },
+        Rule::ConjonctionWeakElimination(premises) => {
+            expect_replaced(s, premises, |p| match p {
+                Prop::Conjonction(a, b) => {
+                    Some(vec![vec![a.as_ref().clone(), b.as_ref().clone()]])
+                }
+                _ => None,
+            })
+        }
+        Rule::Lemma(lemma, premises) => {
+            check_with(lemma.proof(), context).map_err(|_| "invalid proof of the lemma")?;
+            let mut bindings = HashMap::new();
+            if !lemma.sequent().conclusion().matches(c, &mut bindings) {
+                return Err("conclusion is not an instance of the lemma");
+            }
+            let hypotheses = lemma.sequent().hypotheses();
+            let matching = premises.len() == hypotheses.len()
+                && premises.iter().zip(hypotheses).all(|(p, lemma_h)| {
+                    p.sequent().hypotheses() == h
+                        && lemma_h.matches(p.sequent().conclusion(), &mut bindings)
+                });
+            if matching {
+                Ok(())
+            } else {
+                Err("premises don't match the lemma")
+            }
+        }
+        Rule::Unfold(definition, [p]) => {
+            known(definition, context)?;
+            expect_rewritten(s, p, |a| definition.unfold(a))
+        }
+        Rule::Fold(definition, [p]) => {
+            known(definition, context)?;
+            expect_rewritten(s, p, |a| definition.fold(a))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Definition, Lemma};
+
+    fn sequent(s: &str) -> Sequent {
+        s.parse().unwrap()
+    }
+
+    fn hypothesis(s: &str) -> Proof {
+        Proof::hypothesis(sequent(s)).unwrap()
+    }
+
+    fn message(proof: &Proof) -> &'static str {
+        check(proof).unwrap_err().message
+    }
+
+    #[test]
+    fn wrong_premise_conclusion() {
+        let rule = Rule::ConjonctionIntroduction([hypothesis("A, B |- A"), hypothesis("A, B |- B")]);
+        let proof = Proof::tampered(sequent("A, B |- A /\\ A"), rule);
+        assert_eq!(message(&proof), "premise conclusion doesn't match the rule");
+    }
+
+    #[test]
+    fn extra_hypotheses() {
+        let rule = Rule::ImplicationIntroduction([hypothesis("A, A, C |- A")]);
+        let proof = Proof::tampered(sequent("A |- A -> A"), rule);
+        assert_eq!(message(&proof), "premise hypotheses don't match the rule");
+    }
+
+    #[test]
+    fn error_path_of_a_premise() {
+        let tampered = Proof::tampered(sequent("A |- B"), Rule::Hypothesis([]));
+        let proof = Proof::imp_intro(tampered).unwrap();
+        let error = check(&proof).unwrap_err();
+        assert_eq!(error.path, GoalPath::root().child(0));
+        assert_eq!(error.to_string(), "0: conclusion is not a hypothesis");
+    }
+
+    #[test]
+    fn lemma_with_inconsistent_bindings() {
+        let mp = Proof::mp(hypothesis("A -> B, A |- A -> B"), hypothesis("A -> B, A |- A")).unwrap();
+        let lemma = Lemma::new("mp", mp).unwrap();
+        // `A` is bound to `P` by the first premise and to `R` by the second
+        let premises = vec![hypothesis("P -> Q, R |- P -> Q"), hypothesis("P -> Q, R |- R")];
+        let proof = Proof::tampered(sequent("P -> Q, R |- Q"), Rule::Lemma(lemma.into(), premises));
+        assert_eq!(message(&proof), "premises don't match the lemma");
+    }
+
+    #[test]
+    fn rewriting_one_formula() {
+        let xor: Definition = "Xor(A, B) := (A \\/ B) /\\ ~(A /\\ B)".parse().unwrap();
+        let definitions = [xor.clone()];
+        let context = Context {
+            definitions: &definitions,
+            ..Default::default()
+        };
+        let message = |proof: &Proof| check_with(proof, context).unwrap_err().message;
+        let s = sequent("(P \\/ Q) /\\ ~(P /\\ Q) |- Xor(P, Q)");
+        let unfolded = hypothesis("(P \\/ Q) /\\ ~(P /\\ Q) |- (P \\/ Q) /\\ ~(P /\\ Q)");
+        let proof = Proof::tampered(s.clone(), Rule::Unfold(xor.clone().into(), [unfolded]));
+        assert_eq!(check_with(&proof, context), Ok(()));
+        assert_eq!(check(&proof).unwrap_err().message, "unknown definition");
+        // a single formula is rewritten at once
+        let s = sequent("Xor(P, Q) |- Xor(P, Q)");
+        let open = |s: &str| Proof::not_proven(sequent(s));
+        let unfolded = open("(P \\/ Q) /\\ ~(P /\\ Q) |- (P \\/ Q) /\\ ~(P /\\ Q)");
+        let proof = Proof::tampered(s.clone(), Rule::Unfold(xor.clone().into(), [unfolded]));
+        let rewritten = "premise is not the sequent with the definition rewritten in one formula";
+        assert_eq!(message(&proof), rewritten);
+        let proof = Proof::tampered(s.clone(), Rule::Unfold(xor.clone().into(), [open("Xor(P, Q) |- P")]));
+        assert_eq!(message(&proof), rewritten);
+        let proof = Proof::tampered(s, Rule::Fold(xor.into(), [open("Q |- Xor(P, Q)")]));
+        assert_eq!(message(&proof), rewritten);
+    }
+
+    #[test]
+    fn definition_carried_by_the_proof() {
+        let xor: Definition = "Xor(A, B) := (A \\/ B) /\\ ~(A /\\ B)".parse().unwrap();
+        let definitions = [xor];
+        let context = Context {
+            definitions: &definitions,
+            ..Default::default()
+        };
+        // `Xor` unfolds to anything with a forged definition
+        let forged: Definition = "Xor(A, B) := A \\/ B".parse().unwrap();
+        let proof = Proof::tampered(
+            sequent("P |- Xor(P, Q)"),
+            Rule::Unfold(forged.into(), [Proof::not_proven(sequent("P |- P \\/ Q"))]),
+        );
+        let error = check_with(&proof, context).unwrap_err();
+        assert_eq!(error.message, "definition differs from the one of the context");
+    }
+}
@@ -0,0 +1,202 @@
+use super::error::{offset_in, ParseError};
+use super::property::Prop;
+use super::symbols::ReprConf;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A connective defined in a problem file, eg:
+/// `Xor(A, B) := (A \/ B) /\ ~(A /\ B)`, its applications are rewritten to
+/// its body by `unfold` and back by `fold`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    name: String,
+    params: Vec<String>,
+    body: Prop,
+}
+
+/// Applies `f` on the formula, or on its operands when it gives nothing
+fn rewrite(prop: &Prop, f: &impl Fn(&Prop) -> Option<Prop>) -> Prop {
+    if let Some(rewritten) = f(prop) {
+        return rewritten;
+    }
+    match prop {
+        Prop::False | Prop::Variable(_) => prop.clone(),
+        Prop::Conjonction(a, b) => rewrite(a, f).and(&rewrite(b, f)),
+        Prop::Disjonction(a, b) => rewrite(a, f).or(&rewrite(b, f)),
+        Prop::Implication(a, b) => rewrite(a, f).implies(&rewrite(b, f)),
+        Prop::Equivalence(a, b) => Prop::Equivalence(rewrite(a, f).into(), rewrite(b, f).into()),
+        Prop::Defined(name, args) => {
+            Prop::Defined(name.clone(), args.iter().map(|a| rewrite(a, f)).collect())
+        }
+    }
+}
+
+/// Prefixes the names of the applied connectives with the namespace of the
+/// file they were imported from
+pub(crate) fn prefixed(prop: &Prop, namespace: &str) -> Prop {
+    rewrite(prop, &|p| match p {
+        Prop::Defined(name, args) => Some(Prop::Defined(
+            format!("{}.{}", namespace, name),
+            args.iter().map(|a| prefixed(a, namespace)).collect(),
+        )),
+        _ => None,
+    })
+}
+
+fn variables<'a>(prop: &'a Prop, names: &mut Vec<&'a str>) {
+    match prop {
+        Prop::False => {}
+        Prop::Variable(name) => names.push(name),
+        Prop::Conjonction(a, b)
+        | Prop::Disjonction(a, b)
+        | Prop::Implication(a, b)
+        | Prop::Equivalence(a, b) => {
+            variables(a, names);
+            variables(b, names);
+        }
+        Prop::Defined(_, args) => args.iter().for_each(|a| variables(a, names)),
+    }
+}
+
+impl Definition {
+    /// Every parameter must be used in the body, and the body must only use
+    /// the parameters, it can't be a bare parameter, which `fold` would
+    /// rewrite endlessly
+    pub fn new(name: &str, params: Vec<String>, body: Prop) -> Result<Self, &'static str> {
+        if let Prop::Variable(_) = body {
+            return Err("the body is a bare parameter");
+        }
+        if params.iter().enumerate().any(|(i, p)| params[..i].contains(p)) {
+            return Err("parameter declared twice");
+        }
+        let mut used = Vec::new();
+        variables(&body, &mut used);
+        if used.iter().any(|v| !params.iter().any(|p| p == v)) {
+            return Err("the body uses a variable which is not a parameter");
+        }
+        if params.iter().any(|p| !used.contains(&p.as_str())) {
+            return Err("parameter not used in the body");
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            params,
+            body,
+        })
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+    pub fn body(&self) -> &Prop {
+        &self.body
+    }
+    /// Same definition, imported with the namespace, which prefixes its name
+    /// and the connectives its body applies
+    pub(crate) fn namespaced(&self, namespace: &str) -> Self {
+        Self {
+            name: format!("{}.{}", namespace, self.name),
+            params: self.params.clone(),
+            body: prefixed(&self.body, namespace),
+        }
+    }
+    /// Body with the parameters replaced by the arguments, `None` if their
+    /// number differs
+    pub fn expand(&self, args: &[Prop]) -> Option<Prop> {
+        if args.len() != self.params.len() {
+            return None;
+        }
+        let bindings: HashMap<_, _> = self.params.iter().cloned().zip(args.iter().cloned()).collect();
+        Some(self.body.substitute(&bindings))
+    }
+    /// Replaces every application of the connective by its body
+    pub fn unfold(&self, prop: &Prop) -> Prop {
+        rewrite(prop, &|p| match p {
+            Prop::Defined(name, args) if name == &self.name => {
+                let args: Vec<_> = args.iter().map(|a| self.unfold(a)).collect();
+                self.expand(&args)
+            }
+            _ => None,
+        })
+    }
+    /// Replaces every instance of the body by the application of the
+    /// connective, the outermost first
+    pub fn fold(&self, prop: &Prop) -> Prop {
+        rewrite(prop, &|p| {
+            let mut bindings = HashMap::new();
+            if !self.body.matches(p, &mut bindings) {
+                return None;
+            }
+            let args = self.params.iter().map(|param| self.fold(&bindings[param])).collect();
+            Some(Prop::Defined(self.name.clone(), args))
+        })
+    }
+}
+
+/// Reads `Name(A, B) := FORMULA`
+impl std::str::FromStr for Definition {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (head, body) = s.split_once(":=").ok_or_else(|| {
+            ParseError::new(0..s.trim_end().len(), "expecting the body of the definition")
+                .expected(&["Name(A, B) := FORMULA"])
+                .found(s.trim())
+        })?;
+        let head_error = || {
+            let at = offset_in(s, head.trim_start());
+            ParseError::new(at..at + head.trim().len(), "expecting the name and the parameters of the connective")
+                .expected(&["Name(A, B)"])
+                .found(head.trim())
+        };
+        let (name, params) = match head.parse() {
+            Ok(Prop::Defined(name, args)) => {
+                let params = args.into_iter().map(|a| match a {
+                    Prop::Variable(param) => Some(param),
+                    _ => None,
+                });
+                (name, params.collect::<Option<Vec<_>>>().ok_or_else(head_error)?)
+            }
+            _ => return Err(head_error()),
+        };
+        let body_prop = body
+            .parse()
+            .map_err(|e: ParseError| e.offset(offset_in(s, body)))?;
+        Self::new(&name, params, body_prop).map_err(|message| {
+            let at = offset_in(s, body.trim_start());
+            ParseError::new(at..at + body.trim().len(), message).found(body.trim())
+        })
+    }
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conf = ReprConf {
+            negation: true,
+            ..Default::default()
+        };
+        let params = self.params.iter().map(|p| Prop::var(p)).collect();
+        let head = Prop::Defined(self.name.clone(), params);
+        write!(f, "{} := {}", head.repr_conf(conf), self.body.repr_conf(conf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_parameter_is_rejected() {
+        let error = "Id(A) := A".parse::<Definition>().unwrap_err();
+        assert_eq!(error.message, "the body is a bare parameter");
+    }
+
+    #[test]
+    fn fold_terminates_on_nested_instances() {
+        let d: Definition = "Not(A) := A -> !".parse().unwrap();
+        let prop: Prop = "(P -> !) -> !".parse().unwrap();
+        let folded = d.fold(&prop);
+        assert_eq!(folded, "Not(Not(P))".parse().unwrap());
+        assert_eq!(d.unfold(&folded), prop);
+    }
+}
@@ -0,0 +1,119 @@
+use super::proof::{Proof, Rule};
+use super::symbols::ReprConf;
+use std::fmt::Write;
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Symbol of the rule, or name of the lemma
+fn rule_label(rule: &Rule, conf: ReprConf) -> &str {
+    match (rule.get_symbol(), rule) {
+        (Some(symbol), _) => symbol.repr(conf.unicode),
+        (None, Rule::Lemma(lemma, _)) => lemma.name(),
+        (None, _) => "",
+    }
+}
+
+fn write_node(proof: &Proof, conf: ReprConf, next_id: &mut usize, out: &mut String) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    let label = escape(&proof.sequent().repr_conf(conf).to_string());
+    match proof.rule() {
+        None => {
+            let _ = writeln!(
+                out,
+                "  n{} [label=\"{}\", style=filled, color=\"#c03030\", fillcolor=\"#f8d0d0\"];",
+                id, label
+            );
+        }
+        Some(rule) => {
+            let symbol = escape(rule_label(rule, conf));
+            if rule.hypotheses().is_empty() {
+                let _ = writeln!(out, "  n{} [label=\"{}\", xlabel=\"{}\"];", id, label, symbol);
+            } else {
+                let _ = writeln!(out, "  n{} [label=\"{}\"];", id, label);
+            }
+            for p in rule.hypotheses() {
+                let child = write_node(p, conf, next_id, out);
+                let _ = writeln!(out, "  n{} -> n{} [label=\"{}\"];", id, child, symbol);
+            }
+        }
+    }
+    id
+}
+
+impl Proof {
+    /// Exports the proof tree in the Graphviz DOT language, each node is a
+    /// sequent and each edge goes from a conclusion to one of its premises,
+    /// labelled with the applied rule, sequents not proven yet are highlighted
+    pub fn to_dot(&self) -> String {
+        let conf = ReprConf {
+            negation: true,
+            unicode: true,
+            ..Default::default()
+        };
+        let mut out = String::from(
+            "digraph proof {\n  rankdir=BT;\n  node [shape=box, fontname=\"monospace\"];\n  \
+             edge [dir=back, fontname=\"monospace\"];\n",
+        );
+        write_node(self, conf, &mut 0, &mut out);
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Lemma, ProveBy};
+
+    fn conf(unicode: bool) -> ReprConf {
+        ReprConf {
+            negation: true,
+            unicode,
+            ..Default::default()
+        }
+    }
+
+    fn dot(proof: &Proof, conf: ReprConf) -> String {
+        let mut out = String::new();
+        write_node(proof, conf, &mut 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn labels_edges_with_the_rule() {
+        let mut proof = Proof::not_proven("|- A -> A".parse().unwrap());
+        assert!(proof.prove_next_by(ProveBy::ImplicationIntroduction));
+        assert!(proof.prove_next_by(ProveBy::Hypothesis));
+        let unicode = dot(&proof, conf(true));
+        assert_eq!(
+            unicode,
+            "  n0 [label=\"⊢ A➔A\"];\n  n1 [label=\"A ⊢ A\", xlabel=\"h\"];\n  n0 -> n1 [label=\"➔i\"];\n"
+        );
+        let ascii = dot(&proof, conf(false));
+        assert!(ascii.contains("n0 [label=\"|- A->A\"]"));
+        assert!(ascii.contains("n0 -> n1 [label=\"->i\"]"));
+    }
+
+    #[test]
+    fn labels_lemmas_with_their_name() {
+        let mut proof = Proof::not_proven("A |- A \\/ B".parse().unwrap());
+        assert!(proof.prove_next_by(ProveBy::DisjonctionIntroductionLeft));
+        assert!(proof.prove_next_by(ProveBy::Hypothesis));
+        let lemma = Lemma::new("or_left", proof).unwrap();
+        let mut proof = Proof::not_proven("C |- C \\/ D".parse().unwrap());
+        assert!(proof.prove_by_lemma(&lemma, &[]));
+        let out = proof.to_dot();
+        assert!(out.starts_with("digraph proof {\n"));
+        assert!(out.contains("  n0 -> n1 [label=\"or_left\"];"));
+        assert!(out.ends_with("}\n"));
+    }
+
+    #[test]
+    fn highlights_open_goals() {
+        let proof = Proof::not_proven("|- A".parse().unwrap());
+        assert_eq!(dot(&proof, conf(true)), "  n0 [label=\"⊢ A\", style=filled, color=\"#c03030\", fillcolor=\"#f8d0d0\"];\n");
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Error while parsing, `span` is the range of bytes of the input where the
+/// error was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub message: &'static str,
+    pub expected: Vec<&'static str>,
+    pub found: Option<String>,
+}
+impl ParseError {
+    pub fn new(span: Range<usize>, message: &'static str) -> Self {
+        Self {
+            span,
+            message,
+            expected: Vec::new(),
+            found: None,
+        }
+    }
+    pub fn expected(mut self, expected: &[&'static str]) -> Self {
+        self.expected = expected.to_owned();
+        self
+    }
+    pub fn found(mut self, found: &str) -> Self {
+        self.found = Some(found.to_owned());
+        self
+    }
+    /// Moves the span, for errors found in a part of a larger input
+    pub fn offset(mut self, n: usize) -> Self {
+        self.span = self.span.start + n..self.span.end + n;
+        self
+    }
+    /// Shows the line of the input where the error was found, with carets
+    /// under the offending characters
+    pub fn snippet<'a>(&'a self, input: &'a str) -> Snippet<'a> {
+        Snippet { error: self, input }
+    }
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)?;
+        if let Some((last, first)) = self.expected.split_last() {
+            ", expected ".fmt(f)?;
+            for e in first {
+                write!(f, "`{}`, ", e)?;
+            }
+            if !first.is_empty() {
+                "or ".fmt(f)?;
+            }
+            write!(f, "`{}`", last)?;
+        }
+        match &self.found {
+            Some(found) if found.is_empty() => ", found end of input".fmt(f),
+            Some(found) => write!(f, ", found `{}`", found),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Offset of a slice in the string it was taken from
+pub(crate) fn offset_in(outer: &str, inner: &str) -> usize {
+    inner.as_ptr() as usize - outer.as_ptr() as usize
+}
+
+pub struct Snippet<'a> {
+    error: &'a ParseError,
+    input: &'a str,
+}
+impl<'a> fmt::Display for Snippet<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start = self.error.span.start.min(self.input.len());
+        let end = self.error.span.end.clamp(start, self.input.len());
+        let line_start = self.input[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = self.input[start..]
+            .find('\n')
+            .map(|i| start + i)
+            .unwrap_or_else(|| self.input.len());
+        let line = &self.input[line_start..line_end];
+        writeln!(f, "{}", line)?;
+        for c in self.input[line_start..start].chars() {
+            if c == '\t' { '\t' } else { ' ' }.fmt(f)?;
+        }
+        let carets = self.input[start..end.min(line_end)].chars().count().max(1);
+        "^".repeat(carets).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Problem, Sequent};
+
+    fn sequent_error(s: &str) -> String {
+        let e = s.parse::<Sequent>().unwrap_err();
+        format!("{}\n{}", e, e.snippet(s))
+    }
+
+    fn problem_error(s: &str) -> String {
+        let e = Problem::parse_all(s).unwrap_err();
+        format!("{}\n{}", e, e.error.snippet(s))
+    }
+
+    #[test]
+    fn carets_under_sequent_errors() {
+        assert_eq!(
+            sequent_error("A |- B ) C"),
+            "unexpected closing parenthesis, found `)`\nA |- B ) C\n       ^"
+        );
+        assert_eq!(
+            sequent_error("A |- foo.B"),
+            "only defined connectives have a namespace, found `foo.B`\nA |- foo.B\n     ^^^^^"
+        );
+    }
+
+    #[test]
+    fn caret_after_the_end_of_input() {
+        assert_eq!(
+            sequent_error("A, B |- A /\\"),
+            "empty expression, expected `formula`\nA, B |- A /\\\n            ^"
+        );
+    }
+
+    #[test]
+    fn carets_on_the_line_of_problem_errors() {
+        assert_eq!(
+            problem_error("# intro\ntheorem first: A |- A\n    logic: modal\n"),
+            "line 3: unknown logic, expected `minimal`, `intuitionistic`, or `classical`, found `modal`\n\
+             \x20   logic: modal\n\
+             \x20          ^^^^^"
+        );
+        assert_eq!(
+            problem_error("theorem p: A |- A\n\tcolour: red\n"),
+            "line 2: unknown setting, expected `rules`, `logic`, `difficulty`, or `hint`, found `colour`\n\
+             \tcolour: red\n\
+             \t^^^^^^"
+        );
+    }
+
+    #[test]
+    fn line_of_an_error_in_a_continued_sequent() {
+        let e = Problem::parse_all("theorem p: A |-\n    B ->\n    (C\n").unwrap_err();
+        assert_eq!(e.line, 3);
+        assert_eq!(e.error.message, "closing parenthesis expected");
+    }
+}
@@ -0,0 +1,54 @@
+use super::proof::Proof;
+use super::property::Prop;
+use super::sequent::Sequent;
+use std::collections::HashMap;
+
+/// A proven sequent applied by name with `use <name>`, it proves the goals
+/// whose conclusion is an instance of its conclusion, once the instances of
+/// its hypotheses are proven, eg: `syl: A->B, B->C |- A->C` proves
+/// `|- P->R` from `|- P->Q` and `|- Q->R` with `use syl B:=Q`
+#[derive(Debug, Clone)]
+pub struct Lemma {
+    name: String,
+    proof: Proof,
+}
+impl Lemma {
+    /// `None` if the proof is not complete
+    pub fn new(name: &str, proof: Proof) -> Option<Self> {
+        if proof.is_complete() {
+            Some(Self {
+                name: name.to_owned(),
+                proof,
+            })
+        } else {
+            None
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn sequent(&self) -> &Sequent {
+        self.proof.sequent()
+    }
+    pub fn proof(&self) -> &Proof {
+        &self.proof
+    }
+    /// Sequents to prove to apply the lemma on `s`: the hypotheses of the
+    /// lemma, with the variables substituted so that its conclusion becomes
+    /// the one of `s`, and with the given values for the other variables,
+    /// each under the hypotheses of `s`, `None` if the conclusions don't
+    /// match
+    pub fn premises(&self, s: &Sequent, values: &[(String, Prop)]) -> Option<Vec<Sequent>> {
+        let mut bindings: HashMap<_, _> = values.iter().cloned().collect();
+        if !self.sequent().conclusion().matches(s.conclusion(), &mut bindings) {
+            return None;
+        }
+        let premises = self
+            .sequent()
+            .hypotheses()
+            .iter()
+            .map(|h| Sequent::new(s.hypotheses().to_owned(), h.substitute(&bindings)))
+            .collect();
+        Some(premises)
+    }
+}
@@ -1,11 +1,33 @@
+mod error;
 mod property;
 mod sequent;
 mod symbols;
 mod proof;
 mod prove_by;
+mod script;
+mod check;
+mod svg;
+mod dot;
+mod tptp;
+mod sexpr;
+mod pretty;
+mod problem;
+mod theory;
+mod search;
+mod lemma;
+mod definition;
 
 pub use property::{Prop, PropRepr};
 pub use sequent::{Sequent, SequentRepr};
-pub use proof::{Proof, ProofRepr};
+pub use sexpr::SexprRepr;
+pub use proof::{Context, GoalPath, Proof, ProofLayout, ProofRepr, Rule};
+pub use problem::{Logic, Problem, ProblemError};
+pub use theory::{LoadError, Theory};
 pub use prove_by::ProveBy;
-pub use symbols::ReprConf;
\ No newline at end of file
+pub use error::{ParseError, Snippet};
+pub use check::{check, check_with, CheckError};
+pub use lemma::Lemma;
+pub use definition::Definition;
+pub use search::search;
+pub use script::{ReplayError, Script, ScriptError, Step};
+pub use symbols::{ReprConf, Sym};
\ No newline at end of file
@@ -0,0 +1,274 @@
+use super::property::{Precedence, Prop};
+use super::sequent::Sequent;
+use super::symbols::{ReprConf, Sym};
+use std::fmt;
+
+/// Document of the pretty printer, laid out on one line when it fits in the
+/// target width, or broken at its `Line`s and `Break`s otherwise
+pub(crate) enum Doc {
+    /// Text with its width on the screen, without the formating sequences
+    Text(String, usize),
+    /// Nothing on a flat line, a new line otherwise
+    Break,
+    /// A space on a flat line, a new line otherwise
+    Line,
+    Nest(usize, Box<Doc>),
+    /// Flat if all of it fits on the line, broken otherwise
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+struct SymRepr(Sym, ReprConf);
+impl fmt::Display for SymRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(self.1, f)
+    }
+}
+
+fn sym(sym: Sym, conf: ReprConf) -> Doc {
+    Doc::Text(SymRepr(sym, conf).to_string(), sym.len(conf.unicode))
+}
+
+fn paren(doc: Doc, conf: ReprConf) -> Doc {
+    Doc::Concat(vec![
+        sym(Sym::OpenParenthesis, conf),
+        Doc::Nest(1, doc.into()),
+        sym(Sym::CloseParenthesis, conf),
+    ])
+}
+
+/// Same text as `PropRepr`, breaking lines before the weakest connectives,
+/// chains of the same connective are broken all at once
+pub(crate) fn prop_doc(prop: &Prop, conf: ReprConf) -> Doc {
+    let preced = prop.precedence(conf.negation);
+    let (symbol, lhs, rhs) = match prop {
+        Prop::False | Prop::Variable(_) | Prop::Defined(_, _) => {
+            let mut plain = conf;
+            plain.formated = false;
+            let width = prop.repr_conf(plain).to_string().chars().count();
+            return Doc::Text(prop.repr_conf(conf).to_string(), width);
+        }
+        Prop::Implication(lhs, _) if preced == Precedence::NEGATION => {
+            let operand = prop_doc(lhs, conf);
+            let operand = if lhs.precedence(conf.negation) > preced {
+                paren(operand, conf)
+            } else {
+                operand
+            };
+            return Doc::Concat(vec![sym(Sym::Negation, conf), operand]);
+        }
+        Prop::Conjonction(lhs, rhs) => (Sym::Conjonction, lhs, rhs),
+        Prop::Disjonction(lhs, rhs) => (Sym::Disjonction, lhs, rhs),
+        Prop::Implication(lhs, rhs) => (Sym::Implication, lhs, rhs),
+        Prop::Equivalence(lhs, rhs) => (Sym::Equivalence, lhs, rhs),
+    };
+    let mut operands = vec![lhs.as_ref()];
+    let mut last = rhs.as_ref();
+    while last.precedence(conf.negation) == preced {
+        match last {
+            Prop::Conjonction(lhs, rhs)
+            | Prop::Disjonction(lhs, rhs)
+            | Prop::Implication(lhs, rhs)
+            | Prop::Equivalence(lhs, rhs) => {
+                operands.push(lhs);
+                last = rhs;
+            }
+            _ => break,
+        }
+    }
+    let doc = |p: &Prop, parenthesized: bool| {
+        if parenthesized {
+            paren(prop_doc(p, conf), conf)
+        } else {
+            prop_doc(p, conf)
+        }
+    };
+    let mut rest = Vec::new();
+    for p in operands.iter().skip(1) {
+        rest.extend([Doc::Break, sym(symbol, conf), doc(p, p.precedence(conf.negation) >= preced)]);
+    }
+    rest.extend([Doc::Break, sym(symbol, conf), doc(last, last.precedence(conf.negation) > preced)]);
+    let first = operands[0];
+    Doc::Group(
+        Doc::Concat(vec![
+            doc(first, first.precedence(conf.negation) >= preced),
+            Doc::Nest(2, Doc::Concat(rest).into()),
+        ])
+        .into(),
+    )
+}
+
+/// Same text as `SequentRepr`, with one hypothesis by line when broken
+pub(crate) fn sequent_doc(sequent: &Sequent, conf: ReprConf) -> Doc {
+    let mut docs = Vec::new();
+    for (i, h) in sequent.hypotheses().iter().enumerate() {
+        if i > 0 {
+            docs.extend([sym(Sym::Comma, conf), Doc::Line]);
+        }
+        docs.push(prop_doc(h, conf));
+    }
+    if !sequent.hypotheses().is_empty() {
+        docs.push(Doc::Line);
+    }
+    let mut emph_conf = conf;
+    emph_conf.emphazis = true;
+    docs.extend([
+        sym(Sym::Sequent, conf),
+        Doc::Text(" ".to_owned(), 1),
+        Doc::Nest(2, prop_doc(sequent.conclusion(), emph_conf).into()),
+    ]);
+    Doc::Group(Doc::Concat(docs).into())
+}
+
+/// Whether the first document, then the others until the next line break,
+/// fit in `width`
+fn fits(mut width: usize, first: &Doc, rest: &[(usize, bool, &Doc)]) -> bool {
+    let mut stack = vec![(true, first)];
+    let mut rest = rest.iter().rev();
+    loop {
+        let (flat, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, flat, doc)) => (*flat, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(_, w) => match width.checked_sub(*w) {
+                Some(w) => width = w,
+                None => return false,
+            },
+            Doc::Break | Doc::Line if !flat => return true,
+            Doc::Break => {}
+            Doc::Line => match width.checked_sub(1) {
+                Some(w) => width = w,
+                None => return false,
+            },
+            Doc::Nest(_, doc) | Doc::Group(doc) => stack.push((flat, doc)),
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (flat, doc))),
+        }
+    }
+}
+
+impl Doc {
+    /// Lays out the document in `width` columns when possible, returns the
+    /// lines with their width on the screen
+    pub(crate) fn render(&self, width: usize) -> Vec<(String, usize)> {
+        let mut lines = vec![(String::new(), 0)];
+        let mut stack = vec![(0, false, self)];
+        while let Some((indent, flat, doc)) = stack.pop() {
+            let (line, column) = lines.last_mut().expect("current line");
+            match doc {
+                Doc::Text(s, w) => {
+                    line.push_str(s);
+                    *column += w;
+                }
+                Doc::Break if flat => {}
+                Doc::Line if flat => {
+                    line.push(' ');
+                    *column += 1;
+                }
+                Doc::Break | Doc::Line => lines.push((" ".repeat(indent), indent)),
+                Doc::Nest(n, doc) => stack.push((indent + n, flat, doc)),
+                Doc::Group(doc) => {
+                    let flat = flat || fits(width.saturating_sub(*column), doc, &stack);
+                    stack.push((indent, flat, doc));
+                }
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, flat, doc))),
+            }
+        }
+        lines
+    }
+    /// Lays out the document in `width` columns when possible
+    pub(crate) fn pretty(&self, width: usize) -> String {
+        let lines: Vec<_> = self.render(width).into_iter().map(|(line, _)| line).collect();
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Proof;
+
+    fn text(s: &str) -> Doc {
+        Doc::Text(s.to_owned(), s.len())
+    }
+
+    fn group(docs: Vec<Doc>) -> Doc {
+        Doc::Group(Doc::Concat(docs).into())
+    }
+
+    fn conf() -> ReprConf {
+        ReprConf {
+            negation: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_are_flat_when_they_fit() {
+        let doc = group(vec![text("aaa"), Doc::Nest(2, Doc::Concat(vec![Doc::Line, text("bbb")]).into())]);
+        assert_eq!(doc.pretty(7), "aaa bbb");
+        assert_eq!(doc.pretty(6), "aaa\n  bbb");
+        let doc = group(vec![text("aaa"), Doc::Break, text("bbb")]);
+        assert_eq!(doc.pretty(6), "aaabbb");
+        assert_eq!(doc.pretty(5), "aaa\nbbb");
+    }
+
+    #[test]
+    fn inner_groups_stay_flat_in_broken_ones() {
+        let inner = group(vec![text("b"), Doc::Line, text("c")]);
+        let doc = group(vec![text("aaaa"), Doc::Line, Doc::Nest(2, inner.into()), Doc::Line, text("d")]);
+        assert_eq!(doc.pretty(10), "aaaa b c d");
+        assert_eq!(doc.pretty(9), "aaaa\nb c\nd");
+        assert_eq!(doc.pretty(2), "aaaa\nb\n  c\nd");
+    }
+
+    #[test]
+    fn text_after_a_group_must_fit_on_its_line() {
+        let doc = Doc::Concat(vec![group(vec![text("a"), Doc::Line, text("b")]), text("cccc")]);
+        assert_eq!(doc.pretty(7), "a bcccc");
+        assert_eq!(doc.pretty(6), "a\nbcccc");
+    }
+
+    #[test]
+    fn chains_are_broken_all_at_once() {
+        let prop: Prop = "(A /\\ B /\\ C) -> (D \\/ E) -> F".parse().unwrap();
+        assert_eq!(prop.repr_conf(conf()).pretty(16), "A/\\B/\\C->D\\/E->F");
+        assert_eq!(prop.repr_conf(conf()).pretty(12), "A/\\B/\\C\n  ->D\\/E\n  ->F");
+        assert_eq!(prop.repr_conf(conf()).pretty(6), "A\n  /\\B\n  /\\C\n  ->D\n    \\/E\n  ->F");
+    }
+
+    #[test]
+    fn one_hypothesis_by_line() {
+        let sequent: Sequent = "A -> B, B /\\ C -> D, E |- (A \\/ C) -> D".parse().unwrap();
+        assert_eq!(sequent.repr_conf(conf()).pretty(27), "A->B, B/\\C->D, E |- A\\/C->D");
+        assert_eq!(sequent.repr_conf(conf()).pretty(26), "A->B,\nB/\\C->D,\nE\n|- A\\/C->D");
+    }
+
+    #[test]
+    fn proof_layout_within_max_width() {
+        let sequent = "A -> B, B /\\ C -> D, E |- (A -> B) /\\ ((A \\/ C) -> D)".parse().unwrap();
+        let proof = Proof::new(sequent, crate::ProveBy::ConjonctionIntroduction).unwrap();
+        let layout = proof.repr_conf(conf()).max_width(24).layout();
+        let lines: Vec<_> = layout.lines.iter().map(|l| l.trim_end()).collect();
+        assert_eq!(
+            lines,
+            [
+                "                            A->B,",
+                "                            B/\\C->D,",
+                "                            E",
+                "A->B, B/\\C->D, E |- A->B    |- A\\/C->D",
+                "──────────────────────────────────────/\\i",
+                "         A->B,",
+                "         B/\\C->D,",
+                "         E",
+                "         |- (A->B)/\\(A\\/C->D)",
+            ]
+        );
+        assert_eq!(layout.goals, [(0, 3, 24), (28, 3, 10)]);
+        let wide = proof.repr_conf(conf()).layout();
+        assert_eq!(wide.lines.len(), 3);
+    }
+}
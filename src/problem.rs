@@ -0,0 +1,500 @@
+use super::definition::Definition;
+use super::error::{offset_in, ParseError};
+use super::property::Prop;
+use super::proof::{Context, Proof, Rule};
+use super::prove_by::{ProveBy, RULES};
+use super::sequent::Sequent;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Logic in which a problem must be proven
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Logic {
+    /// Without exfalso nor reductio ad absurdum
+    Minimal,
+    /// Without reductio ad absurdum
+    #[default]
+    Intuitionistic,
+    Classical,
+}
+impl Logic {
+    pub fn allows(&self, p: &ProveBy) -> bool {
+        !matches!(
+            (self, p),
+            (Self::Minimal, ProveBy::Exfalso | ProveBy::ReductioAdAbsurdum)
+                | (Self::Intuitionistic, ProveBy::ReductioAdAbsurdum)
+        )
+    }
+}
+impl fmt::Display for Logic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Intuitionistic => "intuitionistic",
+            Self::Classical => "classical",
+        }
+        .fmt(f)
+    }
+}
+impl std::str::FromStr for Logic {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimal" => Ok(Self::Minimal),
+            "intuitionistic" => Ok(Self::Intuitionistic),
+            "classical" => Ok(Self::Classical),
+            _ => Err(ParseError::new(0..s.len(), "unknown logic")
+                .expected(&["minimal", "intuitionistic", "classical"])
+                .found(s)),
+        }
+    }
+}
+
+/// A sequent to prove, with its settings, read from a problem file, eg:
+///
+/// ```text
+/// import "lib/basics.txt" as basics
+/// def Nand(A, B) := ~(A /\ B)
+/// # contraposition
+/// theorem contrapose: ~P->~Q |- ~~Q->~~P
+///     logic: minimal
+///     rules: h, i, ii, iis, mp, e
+///     difficulty: 2
+///     hint: start by introducing the implication
+/// P /\ (Q \/ R)
+///     <-> P /\ Q \/ P /\ R
+/// ```
+///
+/// Empty lines and lines starting with `#` are ignored. A problem starts on
+/// a line that is not indented, with an optional name, and its sequent
+/// continues on the following indented lines, until the settings. A sequent
+/// starting with `(sequent` is read as an s-expression. Imports are resolved
+/// by `Theory::load`. A line `def Name(A, B) := FORMULA` defines a
+/// connective for the problems below it.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub name: Option<String>,
+    pub sequent: Sequent,
+    /// Names of the allowed rules, all of them if `None`
+    pub rules: Option<Vec<String>>,
+    pub logic: Logic,
+    pub difficulty: Option<u32>,
+    pub hint: Option<String>,
+    /// Connectives defined above the problem in its file
+    pub definitions: Vec<Definition>,
+    /// Line of the problem in its file, starts at 1
+    pub line: usize,
+    /// File of the problem, set by `Theory::load`
+    pub path: Option<PathBuf>,
+}
+
+/// Error while reading a problem file, `line` starts at 1 and the span of
+/// the error is relative to the whole file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemError {
+    pub line: usize,
+    pub error: ParseError,
+}
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// Error of `Problem::parse_with`, either in the file or while importing
+pub(crate) enum Failure<E> {
+    Parse(ProblemError),
+    Import(E),
+}
+impl<E> From<ProblemError> for Failure<E> {
+    fn from(e: ProblemError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+fn is_indented(line: &str) -> bool {
+    line.starts_with(char::is_whitespace)
+}
+
+fn is_ignored(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+pub(crate) fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some('A'..='Z') | Some('a'..='z') | Some('_'))
+        && chars.all(|c| matches!(c, 'A'..='Z' | 'a'..='z' | '0'..='9' | '_'))
+}
+
+/// Splits a setting line `key: value`
+fn setting(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.trim().split_once(':')?;
+    if is_name(key) {
+        Some((key, value.trim()))
+    } else {
+        None
+    }
+}
+
+/// Locates the errors found in `part` of the file, which starts on the line
+/// at index `line` and at the byte `at`, but may span several lines
+fn located(line: usize, at: usize, part: &str) -> impl Fn(ParseError) -> ProblemError + '_ {
+    move |e: ParseError| ProblemError {
+        line: line + 1 + part[..e.span.start.min(part.len())].matches('\n').count(),
+        error: e.offset(at),
+    }
+}
+
+/// Reads the end of an import line, `"path" [as name]`
+fn parse_import(s: &str) -> Result<(&str, Option<&str>), ParseError> {
+    let trimmed = s.trim();
+    let at = offset_in(s, trimmed);
+    let error = |part: &str, message| {
+        ParseError::new(0..part.len(), message)
+            .found(part)
+            .offset(offset_in(s, part))
+    };
+    let rest = trimmed
+        .strip_prefix('"')
+        .ok_or_else(|| error(trimmed, "expecting a path between quotes").expected(&["\"path\""]))?;
+    let (path, rest) = rest.split_once('"').ok_or_else(|| {
+        ParseError::new(at..at + 1, "unterminated path")
+            .expected(&["\""])
+            .found("")
+    })?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Ok((path, None));
+    }
+    let namespace = rest
+        .strip_prefix("as")
+        .filter(|r| r.starts_with(char::is_whitespace))
+        .map(str::trim)
+        .ok_or_else(|| error(rest, "unexpected text after the path").expected(&["as"]))?;
+    if !is_name(namespace) {
+        return Err(error(namespace, "invalid namespace"));
+    }
+    Ok((path, Some(namespace)))
+}
+
+/// Checks that the connectives applied in the formula are defined, with the
+/// right number of arguments, the error is located at the first application
+/// of the faulty connective in `s`
+fn check_defined(s: &str, prop: &Prop, definitions: &[Definition]) -> Result<(), ParseError> {
+    let error = |name: &str, message| {
+        let at = s.find(&format!("{}(", name)).unwrap_or(0);
+        ParseError::new(at..at + name.len(), message).found(name)
+    };
+    match prop {
+        Prop::False | Prop::Variable(_) => Ok(()),
+        Prop::Conjonction(a, b)
+        | Prop::Disjonction(a, b)
+        | Prop::Implication(a, b)
+        | Prop::Equivalence(a, b) => {
+            check_defined(s, a, definitions)?;
+            check_defined(s, b, definitions)
+        }
+        Prop::Defined(name, args) => {
+            match definitions.iter().find(|d| d.name() == name) {
+                None => return Err(error(name, "unknown connective")),
+                Some(d) if d.params().len() != args.len() => {
+                    return Err(error(name, "wrong number of arguments"))
+                }
+                Some(_) => {}
+            }
+            args.iter().try_for_each(|a| check_defined(s, a, definitions))
+        }
+    }
+}
+
+fn parse_sequent(s: &str) -> Result<Sequent, ParseError> {
+    if s.trim_start().starts_with("(sequent") {
+        Sequent::from_sexpr(s)
+    } else {
+        s.parse()
+    }
+}
+
+impl Problem {
+    /// A problem without name nor settings
+    pub fn new(sequent: Sequent) -> Self {
+        Self {
+            name: None,
+            sequent,
+            rules: None,
+            logic: Logic::default(),
+            difficulty: None,
+            hint: None,
+            definitions: Vec::new(),
+            line: 1,
+            path: None,
+        }
+    }
+    /// Whether the rule can be used to prove this problem
+    pub fn allows(&self, p: &ProveBy) -> bool {
+        self.logic.allows(p)
+            && self
+                .rules
+                .as_ref()
+                .map(|rules| rules.iter().any(|r| r == p.name()))
+                .unwrap_or(true)
+    }
+    /// Same as `allows`, and `use <name>` is only allowed if the proof of the
+    /// lemma uses allowed rules, so that lemmas don't bring in other rules
+    pub fn allows_with(&self, p: &ProveBy, context: Context<'_>) -> bool {
+        self.allows(p)
+            && match p {
+                ProveBy::Use(name, _) => context.lemma(name).is_none_or(|lemma| self.allows_proof(lemma.proof())),
+                _ => true,
+            }
+    }
+    /// Whether all the rules of the proof, and of the lemmas it applies, can
+    /// be used to prove this problem
+    pub fn allows_proof(&self, proof: &Proof) -> bool {
+        let rule = match proof.rule() {
+            Some(rule) => rule,
+            None => return true,
+        };
+        let logic = !matches!(
+            (self.logic, rule),
+            (Logic::Minimal, Rule::Exfalso(_) | Rule::ReductioAdAbsurdum(_))
+                | (Logic::Intuitionistic, Rule::ReductioAdAbsurdum(_))
+        );
+        let listed = self
+            .rules
+            .as_ref()
+            .map(|rules| rule.commands().iter().any(|c| rules.iter().any(|r| r == c)))
+            .unwrap_or(true);
+        let lemma = match rule {
+            Rule::Lemma(lemma, _) => self.allows_proof(lemma.proof()),
+            _ => true,
+        };
+        logic && listed && lemma && rule.hypotheses().iter().all(|p| self.allows_proof(p))
+    }
+    /// Reads all the problems of a problem file, imports are only supported
+    /// when loading a file with `Theory::load`
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, ProblemError> {
+        Self::parse_with(s, |line, path, _| {
+            Err(ProblemError {
+                line,
+                error: ParseError::new(0..path.len(), "imports are only supported when loading a file")
+                    .offset(offset_in(s, path)),
+            })
+        })
+        .map(|(problems, _)| problems)
+        .map_err(|e| match e {
+            Failure::Parse(e) | Failure::Import(e) => e,
+        })
+    }
+    /// Reads all the problems of a problem file, and the connectives it
+    /// defines, calling `import` with the line, the path and the namespace of
+    /// each `import "path" [as name]`, which gives the imported connectives
+    pub(crate) fn parse_with<'a, E>(
+        s: &'a str,
+        mut import: impl FnMut(usize, &'a str, Option<&'a str>) -> Result<Vec<Definition>, E>,
+    ) -> Result<(Vec<Self>, Vec<Definition>), Failure<E>> {
+        let error = |line: usize, part: &'a str| located(line, offset_in(s, part), part);
+        let mut problems: Vec<Self> = Vec::new();
+        let mut definitions: Vec<Definition> = Vec::new();
+        let mut lines = s.lines().enumerate().peekable();
+        while let Some((i, line)) = lines.next() {
+            if is_ignored(line) {
+                continue;
+            }
+            if is_indented(line) {
+                let line = line.trim();
+                return Err(error(i, line)(ParseError::new(
+                    0..line.len(),
+                    "unexpected indented line, expecting a problem",
+                ))
+                .into());
+            }
+            if let Some(rest) = line.strip_prefix("import").filter(|r| r.starts_with(char::is_whitespace)) {
+                let (path, namespace) = parse_import(rest).map_err(error(i, rest))?;
+                definitions.extend(import(i + 1, path, namespace).map_err(Failure::Import)?);
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("def").filter(|r| r.starts_with(char::is_whitespace)) {
+                let definition: Definition = rest.parse().map_err(error(i, rest))?;
+                check_defined(rest, definition.body(), &definitions).map_err(error(i, rest))?;
+                if definitions.iter().any(|d| d.name() == definition.name()) {
+                    let name = definition.name();
+                    let at = rest.find(name).unwrap_or(0);
+                    return Err(error(i, rest)(
+                        ParseError::new(at..at + name.len(), "connective already defined").found(name),
+                    )
+                    .into());
+                }
+                definitions.push(definition);
+                continue;
+            }
+            let (name, statement) = match line.strip_prefix("theorem") {
+                Some(rest) if rest.starts_with(char::is_whitespace) => {
+                    let (name, statement) = rest.split_once(':').ok_or_else(|| {
+                        error(i, rest)(ParseError::new(rest.len()..rest.len(), "expecting a colon")
+                            .expected(&[":"])
+                            .found(""))
+                    })?;
+                    let name = name.trim();
+                    if !is_name(name) {
+                        return Err(error(i, name)(
+                            ParseError::new(0..name.len(), "invalid theorem name").found(name),
+                        )
+                        .into());
+                    }
+                    if problems.iter().any(|p| p.name.as_deref() == Some(name)) {
+                        return Err(error(i, name)(
+                            ParseError::new(0..name.len(), "theorem already defined").found(name),
+                        )
+                        .into());
+                    }
+                    (Some(name.to_owned()), statement)
+                }
+                _ => (None, line),
+            };
+            let start = offset_in(s, statement);
+            let mut end = start + statement.len();
+            // the statement goes on after ignored lines, which are blanked
+            let (mut ignored, mut skipped) = (Vec::new(), Vec::new());
+            while let Some((_, next)) = lines.peek().copied() {
+                if is_ignored(next) {
+                    skipped.push(next);
+                } else if !is_indented(next) || setting(next).is_some() {
+                    break;
+                } else {
+                    ignored.append(&mut skipped);
+                    end = offset_in(s, next) + next.len();
+                }
+                lines.next();
+            }
+            let mut statement = s[start..end].to_owned();
+            for line in ignored {
+                let at = offset_in(s, line) - start;
+                statement.replace_range(at..at + line.len(), &" ".repeat(line.len()));
+            }
+            let in_statement = located(i, start, &statement);
+            let mut problem = Self::new(parse_sequent(&statement).map_err(&in_statement)?);
+            let sequent = &problem.sequent;
+            for prop in sequent.hypotheses().iter().chain(Some(sequent.conclusion())) {
+                check_defined(&statement, prop, &definitions).map_err(&in_statement)?;
+            }
+            problem.definitions = definitions.clone();
+            problem.name = name;
+            problem.line = i + 1;
+            while let Some((j, next)) = lines.peek().copied() {
+                if is_ignored(next) {
+                    lines.next();
+                    continue;
+                }
+                let (key, value) = match setting(next) {
+                    Some(setting) if is_indented(next) => setting,
+                    _ => break,
+                };
+                lines.next();
+                match key {
+                    "rules" => {
+                        let mut rules = Vec::new();
+                        for rule in value.split(',').map(str::trim) {
+                            if !RULES.contains(&rule) {
+                                return Err(error(j, rule)(
+                                    ParseError::new(0..rule.len(), "unknown rule")
+                                        .expected(RULES)
+                                        .found(rule),
+                                )
+                                .into());
+                            }
+                            rules.push(rule.to_owned());
+                        }
+                        problem.rules = Some(rules);
+                    }
+                    "logic" => problem.logic = value.parse().map_err(error(j, value))?,
+                    "difficulty" => {
+                        problem.difficulty = Some(value.parse().map_err(|_| {
+                            error(j, value)(
+                                ParseError::new(0..value.len(), "not a number")
+                                    .expected(&["number"])
+                                    .found(value),
+                            )
+                        })?)
+                    }
+                    "hint" => {
+                        let hint = problem.hint.get_or_insert_with(String::new);
+                        if !hint.is_empty() {
+                            hint.push('\n');
+                        }
+                        hint.push_str(value);
+                    }
+                    _ => {
+                        return Err(error(j, key)(
+                            ParseError::new(0..key.len(), "unknown setting")
+                                .expected(&["rules", "logic", "difficulty", "hint"])
+                                .found(key),
+                        )
+                        .into())
+                    }
+                }
+            }
+            problems.push(problem);
+        }
+        Ok((problems, definitions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_after_ignored_lines() {
+        let s = "theorem t: A |- A\n\n    # only the hypothesis\n    rules: h\n\n    logic: minimal\n";
+        let problems = Problem::parse_all(s).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].rules, Some(vec!["h".to_owned()]));
+        assert_eq!(problems[0].logic, Logic::Minimal);
+    }
+
+    #[test]
+    fn statement_after_ignored_lines() {
+        let s = "theorem t: A,\n    # the conclusion\n\n    B |- A /\\ B\n    rules: h, ci\n";
+        let problems = Problem::parse_all(s).unwrap();
+        assert_eq!(problems[0].sequent, "A, B |- A /\\ B".parse().unwrap());
+        assert_eq!(problems[0].rules, Some(vec!["h".to_owned(), "ci".to_owned()]));
+        let s = "theorem t: A,\n    # the conclusion\n    B |- A /\\\n";
+        let error = Problem::parse_all(s).unwrap_err();
+        assert_eq!((error.line, &s[error.error.span]), (3, ""));
+    }
+
+    #[test]
+    fn problems_separated_by_ignored_lines() {
+        let s = "A |- A\n\n# second\nB |- B\n    hint: h\n";
+        let problems = Problem::parse_all(s).unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!((problems[0].line, problems[1].line), (1, 4));
+        assert_eq!(problems[1].hint.as_deref(), Some("h"));
+    }
+
+    #[test]
+    fn lemmas_are_restricted_like_rules() {
+        let hypothesis = |s: &str| Proof::hypothesis(s.parse().unwrap()).unwrap();
+        let contradiction = Proof::mp(hypothesis("~~A, ~A |- ~~A"), hypothesis("~~A, ~A |- ~A")).unwrap();
+        let dne = crate::Lemma::new("dne", Proof::raa(contradiction).unwrap()).unwrap();
+        let lemmas = [dne];
+        let context = Context {
+            lemmas: &lemmas,
+            definitions: &[],
+        };
+        let by_dne = ProveBy::Use("dne".to_owned(), Vec::new());
+        let mut problem = Problem::new("~~P |- P".parse().unwrap());
+        assert!(problem.allows(&by_dne));
+        assert!(!problem.allows_with(&by_dne, context));
+        problem.logic = Logic::Classical;
+        assert!(problem.allows_with(&by_dne, context));
+        problem.rules = Some(vec!["use".to_owned(), "raa".to_owned(), "mp".to_owned()]);
+        assert!(!problem.allows_with(&by_dne, context));
+        problem.rules.as_mut().unwrap().push("h".to_owned());
+        assert!(problem.allows_with(&by_dne, context));
+        let unknown = ProveBy::Use("unknown".to_owned(), Vec::new());
+        assert!(problem.allows_with(&unknown, context));
+    }
+}
@@ -1,5 +1,10 @@
 use std::fmt;
+use std::ops::{Add, Div, Sub};
 
+use super::check::check_rule;
+use super::definition::Definition;
+use super::error::ParseError;
+use super::lemma::Lemma;
 use super::property::Prop;
 use super::prove_by::ProveBy;
 use super::sequent::Sequent;
@@ -23,9 +28,19 @@ pub enum Rule {
     ConjonctionIntroduction([Proof; 2]),
     ConjonctionElimination([Proof; 2]),
     ConjonctionWeakElimination([Proof; 1]),
+    ReductioAdAbsurdum([Proof; 1]),
+    /// Application of a lemma, with the instances of its hypotheses
+    Lemma(Box<Lemma>, Vec<Proof>),
+    /// Rewriting of the applications of a defined connective to its body,
+    /// in the conclusion or in a hypothesis
+    Unfold(Box<Definition>, [Proof; 1]),
+    /// Rewriting of the instances of the body of a defined connective to its
+    /// applications, in the conclusion or in a hypothesis
+    Fold(Box<Definition>, [Proof; 1]),
 }
 impl Rule {
-    fn hypotheses(&self) -> &[Proof] {
+    /// The proofs of the premises of the rule
+    pub fn hypotheses(&self) -> &[Proof] {
         match self {
             Self::Hypothesis(v) => v,
             Self::ImplicationIntroduction(v) => v,
@@ -43,6 +58,10 @@ impl Rule {
             Self::ConjonctionIntroduction(v) => v,
             Self::ConjonctionElimination(v) => v,
             Self::ConjonctionWeakElimination(v) => v,
+            Self::ReductioAdAbsurdum(v) => v,
+            Self::Lemma(_, v) => v,
+            Self::Unfold(_, v) => v,
+            Self::Fold(_, v) => v,
         }
     }
     fn hypotheses_mut(&mut self) -> &mut [Proof] {
@@ -63,10 +82,48 @@ impl Rule {
             Self::ConjonctionIntroduction(v) => v,
             Self::ConjonctionElimination(v) => v,
             Self::ConjonctionWeakElimination(v) => v,
+            Self::ReductioAdAbsurdum(v) => v,
+            Self::Lemma(_, v) => v,
+            Self::Unfold(_, v) => v,
+            Self::Fold(_, v) => v,
         }
     }
-    fn get_symbol(&self) -> symbols::Sym {
+    /// Names of the commands that apply this rule, eg: `ii` and `i` for the
+    /// implication introduction
+    pub fn commands(&self) -> &'static [&'static str] {
         match self {
+            Self::Hypothesis(_) => &["h"],
+            Self::ImplicationIntroduction(_) => &["ii", "i"],
+            Self::ImplicationIntroductions(_) => &["iis"],
+            Self::ModusPonens(_) => &["mp"],
+            Self::WeakModusPonens(_) => &["e"],
+            Self::DisjonctionIntroductionLeft(_) => &["dil"],
+            Self::DisjonctionIntroductionRight(_) => &["dir"],
+            Self::DisjonctionElimination(_) => &["de"],
+            Self::DisjonctionWeakElimination(_) => &["e"],
+            Self::Exfalso(_) => &["xf"],
+            Self::EquivalenceIntroduction(_) => &["i"],
+            // Self::EquivalenceElimination(_) => &[],
+            Self::EquivalenceWeakElimination(_) => &["e"],
+            Self::ConjonctionIntroduction(_) => &["ci", "i"],
+            Self::ConjonctionElimination(_) => &["ce"],
+            Self::ConjonctionWeakElimination(_) => &["e"],
+            Self::ReductioAdAbsurdum(_) => &["raa"],
+            Self::Lemma(_, _) => &["use"],
+            Self::Unfold(_, _) => &["unfold"],
+            Self::Fold(_, _) => &["fold"],
+        }
+    }
+    /// Symbol of the rule, or name of the lemma
+    pub(crate) fn label(&self, unicode: bool) -> &str {
+        match self {
+            Self::Lemma(lemma, _) => lemma.name(),
+            _ => self.get_symbol().map_or("", |symbol| symbol.repr(unicode)),
+        }
+    }
+    /// Symbol of the rule, `None` for a lemma
+    pub fn get_symbol(&self) -> Option<symbols::Sym> {
+        Some(match self {
             Self::Hypothesis(_) => symbols::Sym::RuleHypothesis,
             Self::ImplicationIntroduction(_) => symbols::Sym::RuleImplicationIntroduction,
             Self::ImplicationIntroductions(_) => symbols::Sym::RuleImplicationIntroductions,
@@ -83,12 +140,54 @@ impl Rule {
             Self::ConjonctionIntroduction(_) => symbols::Sym::RuleConjonctionIntroduction,
             Self::ConjonctionElimination(_) => symbols::Sym::RuleConjonctionElimination,
             Self::ConjonctionWeakElimination(_) => symbols::Sym::RuleConjonctionWeakElimination,
+            Self::ReductioAdAbsurdum(_) => symbols::Sym::RuleReductioAdAbsurdum,
+            Self::Lemma(_, _) => return None,
+            Self::Unfold(_, _) => symbols::Sym::RuleUnfold,
+            Self::Fold(_, _) => symbols::Sym::RuleFold,
+        })
+    }
+    fn from_lemma(s: &Sequent, lemma: &Lemma, values: &[(String, Prop)]) -> Option<Self> {
+        let premises = lemma.premises(s, values)?;
+        Some(Self::Lemma(
+            lemma.clone().into(),
+            premises.into_iter().map(Proof::not_proven).collect(),
+        ))
+    }
+    /// Unfolds or folds the definition in the conclusion, or in the
+    /// hypothesis at index `at`, `None` if it changes nothing
+    fn from_definition(s: &Sequent, definition: &Definition, unfold: bool, at: Option<usize>) -> Option<Self> {
+        let rewrite = |p: &Prop| {
+            if unfold {
+                definition.unfold(p)
+            } else {
+                definition.fold(p)
+            }
+        };
+        let mut hypotheses = s.hypotheses().to_owned();
+        let mut conclusion = s.conclusion().clone();
+        let target = match at {
+            Some(i) => hypotheses.get_mut(i)?,
+            None => &mut conclusion,
+        };
+        let rewritten = rewrite(target);
+        if &rewritten == target {
+            return None;
         }
+        *target = rewritten;
+        let premise = [Proof::not_proven(Sequent::new(hypotheses, conclusion))];
+        let definition = definition.clone().into();
+        Some(if unfold {
+            Self::Unfold(definition, premise)
+        } else {
+            Self::Fold(definition, premise)
+        })
     }
     fn from(s: &Sequent, p: ProveBy) -> Option<Self> {
         match p {
+            // needs the lemmas and the definitions, see `Proof::prove_with`
+            ProveBy::Use(_, _) | ProveBy::Unfold(_, _) | ProveBy::Fold(_, _) => None,
             ProveBy::Hypothesis => {
-                if s.hypotheses().contains(&s.conclusion()) {
+                if s.hypotheses().contains(s.conclusion()) {
                     Some(Self::Hypothesis([]))
                 } else {
                     None
@@ -151,6 +250,16 @@ impl Rule {
                 s.hypotheses().to_owned(),
                 Prop::False,
             ))])),
+            ProveBy::ReductioAdAbsurdum => {
+                Some(Self::ReductioAdAbsurdum([Proof::not_proven(Sequent::new(
+                    s.hypotheses()
+                        .iter()
+                        .cloned()
+                        .chain(std::iter::once(s.conclusion().negate()))
+                        .collect(),
+                    Prop::False,
+                ))]))
+            }
             ProveBy::DisjonctionElimination(a, b) => Some(Self::DisjonctionElimination([
                 Proof::not_proven(Sequent::new(s.hypotheses().to_owned(), a.or(&b))),
                 Proof::not_proven(Sequent::new(
@@ -182,7 +291,7 @@ impl Rule {
             ProveBy::Eliminate(index) => s
                 .hypotheses()
                 .get(index)
-                .map(|p| match p {
+                .and_then(|p| match p {
                     Prop::Equivalence(lhs, rhs) => {
                         Some(Self::EquivalenceWeakElimination([Proof::not_proven(
                             Sequent::new(
@@ -238,8 +347,7 @@ impl Rule {
                         )),
                     ])),
                     _ => None,
-                })
-                .flatten(),
+                }),
             ProveBy::ConjonctionElimination(a, b) => Some(Self::ConjonctionElimination([
                 Proof::not_proven(Sequent::new(s.hypotheses().to_owned(), a.and(&b))),
                 Proof::not_proven(Sequent::new(
@@ -281,8 +389,19 @@ impl Proof {
             .map(|v| v.hypotheses().iter().all(|v| v.is_complete()))
             .unwrap_or(false)
     }
-    pub fn repr_conf(&self, conf: symbols::ReprConf) -> ProofRepr {
-        ProofRepr { proof: self, conf }
+    pub fn sequent(&self) -> &Sequent {
+        &self.sequent
+    }
+    /// The rule applied to prove the sequent, `None` if not proven yet
+    pub fn rule(&self) -> Option<&Rule> {
+        self.rule.as_deref()
+    }
+    pub fn repr_conf(&self, conf: symbols::ReprConf) -> ProofRepr<'_> {
+        ProofRepr {
+            proof: self,
+            conf,
+            max_width: None,
+        }
     }
     pub fn not_proven(s: Sequent) -> Self {
         Self {
@@ -290,13 +409,13 @@ impl Proof {
             rule: None,
         }
     }
-    pub fn new(s: Sequent, p: ProveBy) -> Self {
-        match p {
-            ProveBy::Hypothesis => {
-                s.hypotheses().contains(&s.conclusion());
-                unimplemented!()
-            }
-            _ => unimplemented!(),
+    /// The sequent proven by the rule, its premises not proven yet
+    pub fn new(s: Sequent, p: ProveBy) -> Result<Self, &'static str> {
+        let mut proof = Self::not_proven(s);
+        if proof.prove_by(p) {
+            Ok(proof)
+        } else {
+            Err("the rule can't be applied to the sequent")
         }
     }
     pub fn prove_by(&mut self, p: ProveBy) -> bool {
@@ -307,6 +426,39 @@ impl Proof {
             false
         }
     }
+    /// Same as `prove_by`, `use <name>` applies the lemma with this name, and
+    /// `unfold <name>` and `fold <name>` rewrite the connective with this name
+    pub fn prove_with(&mut self, p: ProveBy, context: Context<'_>) -> bool {
+        let rule = match &p {
+            ProveBy::Use(name, values) => match context.lemma(name) {
+                Some(lemma) => return self.prove_by_lemma(lemma, values),
+                None => return false,
+            },
+            ProveBy::Unfold(name, at) => context
+                .definition(name)
+                .and_then(|d| Rule::from_definition(&self.sequent, d, true, *at)),
+            ProveBy::Fold(name, at) => context
+                .definition(name)
+                .and_then(|d| Rule::from_definition(&self.sequent, d, false, *at)),
+            _ => return self.prove_by(p),
+        };
+        match rule {
+            Some(r) => {
+                self.rule = Some(r.into());
+                true
+            }
+            None => false,
+        }
+    }
+    /// Applies the lemma, its premises are given by `Lemma::premises`
+    pub fn prove_by_lemma(&mut self, lemma: &Lemma, values: &[(String, Prop)]) -> bool {
+        if let Some(r) = Rule::from_lemma(&self.sequent, lemma, values) {
+            self.rule = Some(r.into());
+            true
+        } else {
+            false
+        }
+    }
     pub fn next_not_proven(&self) -> Option<&Self> {
         if let Some(rule) = self.rule.as_ref() {
             rule.hypotheses()
@@ -318,18 +470,15 @@ impl Proof {
         }
     }
     pub fn next_not_proven_mut(&mut self) -> Option<&mut Self> {
-        if self.rule.is_some() {
-            self.rule
-                .as_mut()
-                .unwrap()
-                .as_mut()
-                .hypotheses_mut()
-                .iter_mut()
-                .filter_map(|p| p.next_not_proven_mut())
-                .next()
-        } else {
-            Some(self)
+        if self.rule.is_none() {
+            return Some(self);
         }
+        self.rule
+            .as_mut()?
+            .hypotheses_mut()
+            .iter_mut()
+            .filter_map(|p| p.next_not_proven_mut())
+            .next()
     }
     pub fn prove_next_by(&mut self, p: ProveBy) -> bool {
         if let Some(n) = self.next_not_proven_mut() {
@@ -340,62 +489,309 @@ impl Proof {
     }
 }
 
+/// Path from the root of a proof to one of its premises, written with the
+/// index of the premise at each level, eg: `0.1` is the second premise of the
+/// first premise, the root is written `root`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoalPath(Vec<usize>);
+impl GoalPath {
+    pub fn root() -> Self {
+        Self(Vec::new())
+    }
+    pub fn indices(&self) -> &[usize] {
+        &self.0
+    }
+    /// Path of the `index`th premise of the sequent at this path
+    pub fn child(&self, index: usize) -> Self {
+        let mut path = self.clone();
+        path.0.push(index);
+        path
+    }
+    /// Whether the sequent at this path is in the proof of the one at `path`
+    pub fn starts_with(&self, path: &Self) -> bool {
+        self.0.starts_with(&path.0)
+    }
+}
+impl fmt::Display for GoalPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return "root".fmt(f);
+        }
+        for (i, index) in self.0.iter().enumerate() {
+            if i > 0 {
+                ".".fmt(f)?;
+            }
+            index.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for GoalPath {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "root" {
+            return Ok(Self::root());
+        }
+        let mut at = 0;
+        let mut path = Vec::new();
+        for index in s.split('.') {
+            path.push(index.parse().map_err(|_| {
+                ParseError::new(at..at + index.len(), "invalid goal path")
+                    .expected(&["root", "number"])
+                    .found(index)
+            })?);
+            at += index.len() + 1;
+        }
+        Ok(Self(path))
+    }
+}
+
+impl Proof {
+    /// Paths of the sequents not proven yet, from left to right
+    pub fn open_goals(&self) -> Vec<GoalPath> {
+        fn collect(proof: &Proof, path: &mut GoalPath, goals: &mut Vec<GoalPath>) {
+            match proof.rule.as_ref() {
+                Some(rule) => {
+                    for (i, p) in rule.hypotheses().iter().enumerate() {
+                        path.0.push(i);
+                        collect(p, path, goals);
+                        path.0.pop();
+                    }
+                }
+                None => goals.push(path.clone()),
+            }
+        }
+        let mut goals = Vec::new();
+        collect(self, &mut GoalPath::root(), &mut goals);
+        goals
+    }
+    /// Number of rules applied in the proof
+    pub fn size(&self) -> usize {
+        self.rule
+            .as_ref()
+            .map(|rule| 1 + rule.hypotheses().iter().map(Proof::size).sum::<usize>())
+            .unwrap_or(0)
+    }
+    /// Proof of the sequent at `path`
+    pub fn goal(&self, path: &GoalPath) -> Option<&Self> {
+        path.0.iter().try_fold(self, |p, i| p.rule.as_ref()?.hypotheses().get(*i))
+    }
+    pub fn goal_mut(&mut self, path: &GoalPath) -> Option<&mut Self> {
+        path.0
+            .iter()
+            .try_fold(self, |p, i| p.rule.as_mut()?.hypotheses_mut().get_mut(*i))
+    }
+    /// Applies the rule on the sequent at `path`, which must not be proven yet
+    pub fn prove_at(&mut self, path: &GoalPath, p: ProveBy) -> bool {
+        self.prove_at_with(path, p, Context::default())
+    }
+    /// Same as `prove_at`, with the lemmas and the definitions the rule may
+    /// refer to
+    pub fn prove_at_with(&mut self, path: &GoalPath, p: ProveBy, context: Context<'_>) -> bool {
+        match self.goal_mut(path) {
+            Some(goal) if goal.rule.is_none() => goal.prove_with(p, context),
+            _ => false,
+        }
+    }
+}
+
+/// Bottom-up construction: each constructor concludes from the proofs of the
+/// premises of a rule, which may still have open goals, and fails when they
+/// don't fit the rule
+impl Proof {
+    fn conclude(s: Sequent, rule: Rule) -> Result<Self, &'static str> {
+        check_rule(&s, &rule, Context::default())?;
+        Ok(Self {
+            sequent: s,
+            rule: Some(rule.into()),
+        })
+    }
+    /// `Γ, A |- A`
+    pub fn hypothesis(s: Sequent) -> Result<Self, &'static str> {
+        Self::conclude(s, Rule::Hypothesis([]))
+    }
+    /// From `Γ, A |- B`, `Γ |- A -> B`, `A` is the last hypothesis
+    pub fn imp_intro(p: Self) -> Result<Self, &'static str> {
+        let (a, h) = p.sequent.hypotheses().split_last().ok_or("the premise has no hypothesis")?;
+        let s = Sequent::new(h.to_owned(), a.implies(p.sequent.conclusion()));
+        Self::conclude(s, Rule::ImplicationIntroduction([p]))
+    }
+    /// From `Γ |- A -> B` and `Γ |- A`, `Γ |- B`
+    pub fn mp(ab: Self, a: Self) -> Result<Self, &'static str> {
+        let b = match ab.sequent.conclusion() {
+            Prop::Implication(_, b) => b.as_ref().clone(),
+            _ => return Err("first premise is not an implication"),
+        };
+        let s = Sequent::new(ab.sequent.hypotheses().to_owned(), b);
+        Self::conclude(s, Rule::ModusPonens([ab, a]))
+    }
+    /// From `Γ |- A`, `Γ |- A \/ B`
+    pub fn or_intro_left(a: Self, b: &Prop) -> Result<Self, &'static str> {
+        let s = Sequent::new(a.sequent.hypotheses().to_owned(), a.sequent.conclusion().or(b));
+        Self::conclude(s, Rule::DisjonctionIntroductionLeft([a]))
+    }
+    /// From `Γ |- B`, `Γ |- A \/ B`
+    pub fn or_intro_right(a: &Prop, b: Self) -> Result<Self, &'static str> {
+        let s = Sequent::new(b.sequent.hypotheses().to_owned(), a.or(b.sequent.conclusion()));
+        Self::conclude(s, Rule::DisjonctionIntroductionRight([b]))
+    }
+    /// From `Γ |- A \/ B`, `Γ, A |- C` and `Γ, B |- C`, `Γ |- C`
+    pub fn or_elim(ab: Self, pa: Self, pb: Self) -> Result<Self, &'static str> {
+        let s = Sequent::new(ab.sequent.hypotheses().to_owned(), pa.sequent.conclusion().clone());
+        Self::conclude(s, Rule::DisjonctionElimination([ab, pa, pb]))
+    }
+    /// From `Γ |- A` and `Γ |- B`, `Γ |- A /\ B`
+    pub fn and_intro(pa: Self, pb: Self) -> Result<Self, &'static str> {
+        let c = pa.sequent.conclusion().and(pb.sequent.conclusion());
+        let s = Sequent::new(pa.sequent.hypotheses().to_owned(), c);
+        Self::conclude(s, Rule::ConjonctionIntroduction([pa, pb]))
+    }
+    /// From `Γ |- A /\ B` and `Γ, A, B |- C`, `Γ |- C`
+    pub fn and_elim(ab: Self, p: Self) -> Result<Self, &'static str> {
+        let s = Sequent::new(ab.sequent.hypotheses().to_owned(), p.sequent.conclusion().clone());
+        Self::conclude(s, Rule::ConjonctionElimination([ab, p]))
+    }
+    /// From `Γ |- A -> B` and `Γ |- B -> A`, `Γ |- A <=> B`
+    pub fn equiv_intro(ab: Self, ba: Self) -> Result<Self, &'static str> {
+        let c = match ab.sequent.conclusion() {
+            Prop::Implication(a, b) => Prop::Equivalence(a.clone(), b.clone()),
+            _ => return Err("first premise is not an implication"),
+        };
+        let s = Sequent::new(ab.sequent.hypotheses().to_owned(), c);
+        Self::conclude(s, Rule::EquivalenceIntroduction([ab, ba]))
+    }
+    /// From `Γ |- False`, `Γ |- C`
+    pub fn exfalso(p: Self, c: &Prop) -> Result<Self, &'static str> {
+        let s = Sequent::new(p.sequent.hypotheses().to_owned(), c.clone());
+        Self::conclude(s, Rule::Exfalso([p]))
+    }
+    /// From `Γ, ~C |- False`, `Γ |- C`, `~C` is the last hypothesis
+    pub fn raa(p: Self) -> Result<Self, &'static str> {
+        let (not_c, h) = p.sequent.hypotheses().split_last().ok_or("the premise has no hypothesis")?;
+        let c = match not_c {
+            Prop::Implication(c, f) if f.as_ref() == &Prop::False => c.as_ref().clone(),
+            _ => return Err("the last hypothesis of the premise is not a negation"),
+        };
+        let s = Sequent::new(h.to_owned(), c);
+        Self::conclude(s, Rule::ReductioAdAbsurdum([p]))
+    }
+}
+
+#[cfg(test)]
+impl Proof {
+    /// Proof applying the rule whatever its premises, to test the kernel
+    pub(crate) fn tampered(sequent: Sequent, rule: Rule) -> Self {
+        Self {
+            sequent,
+            rule: Some(rule.into()),
+        }
+    }
+}
+
+/// Lemmas and definitions that rules refer to by name, see
+/// `Proof::prove_with`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context<'a> {
+    pub lemmas: &'a [Lemma],
+    pub definitions: &'a [Definition],
+}
+impl<'a> Context<'a> {
+    pub fn lemma(&self, name: &str) -> Option<&'a Lemma> {
+        self.lemmas.iter().find(|l| l.name() == name)
+    }
+    pub fn definition(&self, name: &str) -> Option<&'a Definition> {
+        self.definitions.iter().find(|d| d.name() == name)
+    }
+}
+
 pub struct ProofRepr<'a> {
     proof: &'a Proof,
     conf: symbols::ReprConf,
+    max_width: Option<usize>,
 }
 impl<'a> ProofRepr<'a> {
+    /// Sequents wider than `width` are broken on several lines
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+    /// Lines of the sequent with their width
+    fn sequent_lines(&self) -> Vec<(String, usize)> {
+        let mut conf = self.conf;
+        conf.numbered = false;
+        let repr = self.proof.sequent.repr_conf(conf);
+        let width = repr.len();
+        match self.max_width {
+            Some(max_width) if width > max_width => repr.lines(max_width),
+            _ => vec![(repr.to_string(), width)],
+        }
+    }
     fn render(&self) -> ProofRender {
+        let lines = self.sequent_lines();
+        let s_width = lines.iter().map(|(_, width)| *width).max().unwrap_or(0);
         if let Some(rule) = self.proof.rule.as_ref() {
             let mut r = ProofRender::from_iter(
-                rule.hypotheses()
-                    .iter()
-                    .map(|v| v.repr_conf(self.conf).render()),
+                rule.hypotheses().iter().map(|v| ProofRepr {
+                    proof: v,
+                    conf: self.conf,
+                    max_width: self.max_width,
+                }
+                .render()),
                 4,
             );
-            let s_width = self.proof.sequent.repr_conf(self.conf).len();
-            
-            let dr_center = 2 * r.bottom_x + r.bottom_width;
-            let ds_center = s_width;
-            let dcenter = dr_center.max(ds_center);
-            let r_x = (dcenter - dr_center) / 2;
-            let s_x = (dcenter - ds_center) / 2;
-
-            // // let r_center = (r.bottom_x + r.bottom_width) / 2;
-            // // let s_center = s_width / 2;
-            // // let center = r_center.max(s_center);
-            // let r_x = center - r_center;
-            // let s_x = center - s_center;
-
-            r.extend_left(r_x);
-            let line_left = r.bottom_x.min(s_x);
-            let line_right = (r.bottom_x + r.bottom_width).max(s_x + s_width);
+            let Centering {
+                premises_x,
+                sequent_x: s_x,
+                line_left,
+                line_right,
+            } = Centering::new(r.bottom_x, r.bottom_width, s_width);
+            r.extend_left(premises_x);
             let line_width = line_right - line_left;
-            let mut line_str: String =
-                std::iter::repeat(symbols::Sym::RuleLine.repr(self.conf.unicode))
-                    .take(line_width)
-                    .collect::<String>();
-            line_str += rule.get_symbol().repr(self.conf.unicode);
+            let mut line_str = symbols::Sym::RuleLine
+                .repr(self.conf.unicode)
+                .repeat(line_width);
+            let label = rule.label(self.conf.unicode);
+            line_str += label;
             r.write_down_at(
                 line_str,
-                line_width + rule.get_symbol().len(self.conf.unicode),
+                line_width + label.chars().count(),
                 line_left,
             );
-            r.write_down_at(
-                self.proof.sequent.repr_conf(self.conf).to_string(),
-                s_width,
-                s_x,
-            );
+            r.write_lines_at(lines, s_width, s_x);
             r
         } else {
-            let s_width = self.proof.sequent.repr_conf(self.conf).len();
-            ProofRender {
-                width: s_width,
-                height: 1,
-                bottom_x: 0,
-                bottom_width: s_width,
-                buffer: vec![self.proof.sequent.repr_conf(self.conf).to_string()],
-            }
+            let mut r = ProofRender::new();
+            r.write_lines_at(lines, s_width, 0);
+            r.goals.push((0, 0, s_width));
+            r
+        }
+    }
+}
+
+/// Proof tree laid out as lines of text, see `ProofRepr::layout`
+pub struct ProofLayout {
+    /// Lines from top to bottom, all with the same width on the screen
+    pub lines: Vec<String>,
+    pub width: usize,
+    /// Column, line and width of the last line of each open goal, in the
+    /// order of `Proof::open_goals`
+    pub goals: Vec<(usize, usize, usize)>,
+}
+
+impl<'a> ProofRepr<'a> {
+    /// Lays out the proof tree as `Display` does, with the position of the
+    /// open goals, to show only a part of a large proof
+    pub fn layout(&self) -> ProofLayout {
+        let r = self.render();
+        let height = r.height;
+        ProofLayout {
+            lines: r.buffer.into_iter().rev().collect(),
+            width: r.width,
+            goals: r
+                .goals
+                .into_iter()
+                .map(|(x, y, w)| (x, height - 1 - y, w))
+                .collect(),
         }
     }
 }
@@ -407,7 +803,52 @@ impl<'a> fmt::Display for ProofRepr<'a> {
             r.buffer[y].fmt(f)?;
             "\n".fmt(f)?;
         }
-        Ok(())
+        // goal panel of the first open goal
+        match self.proof.open_goals().first().and_then(|g| self.proof.goal(g)) {
+            Some(goal) if self.conf.numbered => {
+                writeln!(f)?;
+                writeln!(f, "{}", goal.sequent.repr_conf(self.conf))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Horizontal layout of a rule, centering the bottom line of its premises
+/// and its sequent on each other, in columns for `ProofRender` or in pixels
+/// for the SVG rendering
+pub(crate) struct Centering<T> {
+    /// Offset of the premises
+    pub premises_x: T,
+    /// Offset of the sequent
+    pub sequent_x: T,
+    /// Ends of the rule line, below the premises and above the sequent
+    pub line_left: T,
+    pub line_right: T,
+}
+impl<T> Centering<T>
+where
+    T: Copy + PartialOrd + From<u8> + Add<Output = T> + Sub<Output = T> + Div<Output = T>,
+{
+    /// `bottom_x` and `bottom_width` are those of the premises laid out side
+    /// by side, `s_width` is the width of the sequent
+    pub(crate) fn new(bottom_x: T, bottom_width: T, s_width: T) -> Self {
+        let max = |a: T, b: T| if a < b { b } else { a };
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let two = T::from(2);
+        let dr_center = bottom_x + bottom_x + bottom_width;
+        let ds_center = s_width;
+        let dcenter = max(dr_center, ds_center);
+        let premises_x = (dcenter - dr_center) / two;
+        let sequent_x = (dcenter - ds_center) / two;
+        let line_left = min(bottom_x + premises_x, sequent_x);
+        let line_right = max(bottom_x + premises_x + bottom_width, sequent_x + s_width);
+        Self {
+            premises_x,
+            sequent_x,
+            line_left,
+            line_right,
+        }
     }
 }
 
@@ -417,6 +858,8 @@ struct ProofRender {
     bottom_x: usize,
     bottom_width: usize,
     buffer: Vec<String>,
+    /// Column, line from the bottom and width of the open goals
+    goals: Vec<(usize, usize, usize)>,
 }
 impl ProofRender {
     fn new() -> Self {
@@ -426,18 +869,20 @@ impl ProofRender {
             bottom_x: 0,
             bottom_width: 0,
             buffer: Vec::new(),
+            goals: Vec::new(),
         }
     }
     fn extend_right(&mut self, n: usize) {
         self.width += n;
         self.buffer
             .iter_mut()
-            .for_each(|l| l.extend(std::iter::repeat(' ').take(n)))
+            .for_each(|l| l.extend(std::iter::repeat_n(' ', n)))
     }
     fn extend_left(&mut self, n: usize) {
         self.width += n;
         self.bottom_x += n;
-        let padding: String = std::iter::repeat(' ').take(n).collect();
+        self.goals.iter_mut().for_each(|g| g.0 += n);
+        let padding = " ".repeat(n);
         self.buffer
             .iter_mut()
             .for_each(|l| l.insert_str(0, &padding))
@@ -445,18 +890,21 @@ impl ProofRender {
     fn extend_up(&mut self, n: usize) {
         self.height += n;
         self.buffer
-            .extend(std::iter::repeat(std::iter::repeat(' ').take(self.width).collect()).take(n));
+            .extend(std::iter::repeat_n(" ".repeat(self.width), n));
     }
     fn push_right(&mut self, rhs: &Self) {
         if self.height < rhs.height {
             self.extend_up(rhs.height - self.height);
         }
         self.bottom_width = self.width - self.bottom_x + rhs.bottom_x + rhs.bottom_width;
+        let width = self.width;
+        self.goals
+            .extend(rhs.goals.iter().map(|(x, y, w)| (x + width, *y, *w)));
         self.width += rhs.width;
         self.buffer
             .iter_mut()
             .zip(rhs.buffer.iter().chain(std::iter::repeat(
-                &std::iter::repeat(' ').take(rhs.width).collect(),
+                &" ".repeat(rhs.width),
             )))
             .for_each(|(l, r)| *l += r);
     }
@@ -477,17 +925,76 @@ impl ProofRender {
         use std::cmp::Ordering;
         match len.cmp(&self.width) {
             Ordering::Greater => self.extend_right(len - self.width),
-            Ordering::Less => s.extend(std::iter::repeat(' ').take(self.width - len)),
+            Ordering::Less => s.push_str(&" ".repeat(self.width - len)),
             _ => {}
         }
         self.height += 1;
         self.buffer.insert(0, s);
+        self.goals.iter_mut().for_each(|g| g.1 += 1);
     }
     fn write_down_at(&mut self, s: String, len: usize, at: usize) {
-        let mut padding: String = std::iter::repeat(' ').take(at).collect();
+        let mut padding = " ".repeat(at);
         padding += &s;
         self.bottom_x = at;
         self.bottom_width = len;
         self.write_down(padding, len + at)
     }
+    /// Writes the lines from top to bottom, as a block of `width` columns
+    fn write_lines_at(&mut self, lines: Vec<(String, usize)>, width: usize, at: usize) {
+        for (line, len) in lines {
+            self.write_down_at(line, len, at);
+        }
+        self.bottom_width = width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequent(s: &str) -> Sequent {
+        s.parse().unwrap()
+    }
+
+    fn hypothesis(s: &str) -> Proof {
+        Proof::hypothesis(sequent(s)).unwrap()
+    }
+
+    #[test]
+    fn new_applies_the_rule() {
+        let proof = Proof::new(sequent("A |- B -> A"), ProveBy::ImplicationIntroduction).unwrap();
+        assert_eq!(proof.open_goals(), vec![GoalPath::root().child(0)]);
+        assert!(Proof::new(sequent("A |- B"), ProveBy::ImplicationIntroduction).is_err());
+    }
+
+    #[test]
+    fn builds_checked_proofs() {
+        let b = Proof::mp(hypothesis("A -> B, A |- A -> B"), hypothesis("A -> B, A |- A")).unwrap();
+        let ab = Proof::and_intro(b, hypothesis("A -> B, A |- A")).unwrap();
+        let proof = Proof::imp_intro(Proof::imp_intro(ab).unwrap()).unwrap();
+        assert_eq!(proof.sequent(), &sequent("|- (A -> B) -> A -> B /\\ A"));
+        assert!(proof.is_complete());
+        assert_eq!(crate::check(&proof), Ok(()));
+        let contradiction = Proof::mp(hypothesis("A, ~A |- ~A"), hypothesis("A, ~A |- A")).unwrap();
+        let proof = Proof::raa(contradiction).unwrap();
+        assert_eq!(proof.sequent(), &sequent("A |- A"));
+        assert_eq!(crate::check(&proof), Ok(()));
+    }
+
+    #[test]
+    fn rejects_premises_which_do_not_fit() {
+        let error = Proof::and_intro(hypothesis("A |- A"), hypothesis("B |- B")).unwrap_err();
+        assert_eq!(error, "premise hypotheses don't match the rule");
+        let error = Proof::mp(hypothesis("A -> B, C |- A -> B"), hypothesis("A -> B, C |- C")).unwrap_err();
+        assert_eq!(error, "premise conclusion doesn't match the rule");
+        let error = Proof::mp(hypothesis("A |- A"), hypothesis("A |- A")).unwrap_err();
+        assert_eq!(error, "first premise is not an implication");
+        let error = Proof::imp_intro(Proof::not_proven(sequent("|- A"))).unwrap_err();
+        assert_eq!(error, "the premise has no hypothesis");
+        let error = Proof::raa(hypothesis("A, B |- B")).unwrap_err();
+        assert_eq!(error, "the last hypothesis of the premise is not a negation");
+        let error = Proof::or_elim(hypothesis("A \\/ B |- A \\/ B"), hypothesis("A \\/ B, A |- A"), hypothesis("A \\/ B, B |- B"));
+        assert_eq!(error.unwrap_err(), "premise conclusion doesn't match the rule");
+        assert_eq!(Proof::hypothesis(sequent("A |- B")).unwrap_err(), "conclusion is not a hypothesis");
+    }
 }
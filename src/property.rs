@@ -1,8 +1,10 @@
+use super::pretty;
 use super::symbols;
+use std::collections::HashMap;
 use std::fmt;
 
 /// Represents any property, eg: `A/\B->B`
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Prop {
     False,
     Variable(String),
@@ -10,6 +12,9 @@ pub enum Prop {
     Disjonction(Box<Prop>, Box<Prop>),
     Implication(Box<Prop>, Box<Prop>),
     Equivalence(Box<Prop>, Box<Prop>),
+    /// Application of a connective defined in a problem file, eg:
+    /// `Xor(A, B)`, see `Definition`
+    Defined(String, Vec<Prop>),
 }
 
 impl Prop {
@@ -34,16 +39,60 @@ impl Prop {
         self.implies(&Self::False)
     }
     /// Wraps a property to control the way it will be displayed
-    pub fn repr(&self) -> PropRepr {
+    pub fn repr(&self) -> PropRepr<'_> {
         self.repr_conf(Default::default())
     }
-    pub fn repr_conf(&self, conf: symbols::ReprConf) -> PropRepr {
+    pub fn repr_conf(&self, conf: symbols::ReprConf) -> PropRepr<'_> {
         PropRepr { prop: self, conf }
     }
-    fn precedence(&self, negation: bool) -> Precedence {
+    /// Binds the variables of `self`, used as a pattern, so that it becomes
+    /// `prop`, consistently with the bindings already made, returns whether
+    /// it is possible, the bindings may be partially filled when it is not
+    pub fn matches(&self, prop: &Self, bindings: &mut HashMap<String, Self>) -> bool {
+        match (self, prop) {
+            (Self::Variable(name), _) => match bindings.get(name) {
+                Some(bound) => bound == prop,
+                None => {
+                    bindings.insert(name.clone(), prop.clone());
+                    true
+                }
+            },
+            (Self::False, Self::False) => true,
+            (Self::Defined(f, a), Self::Defined(g, b)) if f == g && a.len() == b.len() => {
+                a.iter().zip(b).all(|(a, b)| a.matches(b, bindings))
+            }
+            (Self::Conjonction(a, b), Self::Conjonction(c, d))
+            | (Self::Disjonction(a, b), Self::Disjonction(c, d))
+            | (Self::Implication(a, b), Self::Implication(c, d))
+            | (Self::Equivalence(a, b), Self::Equivalence(c, d)) => {
+                a.matches(c, bindings) && b.matches(d, bindings)
+            }
+            _ => false,
+        }
+    }
+    /// Replaces the bound variables by their values
+    pub fn substitute(&self, bindings: &HashMap<String, Self>) -> Self {
+        match self {
+            Self::False => Self::False,
+            Self::Variable(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Self::Conjonction(a, b) => a.substitute(bindings).and(&b.substitute(bindings)),
+            Self::Disjonction(a, b) => a.substitute(bindings).or(&b.substitute(bindings)),
+            Self::Implication(a, b) => a.substitute(bindings).implies(&b.substitute(bindings)),
+            Self::Equivalence(a, b) => Self::Equivalence(
+                a.substitute(bindings).into(),
+                b.substitute(bindings).into(),
+            ),
+            Self::Defined(name, args) => Self::Defined(
+                name.clone(),
+                args.iter().map(|a| a.substitute(bindings)).collect(),
+            ),
+        }
+    }
+    pub(crate) fn precedence(&self, negation: bool) -> Precedence {
         match self {
             Self::False => Precedence::ATOMIC,
             Self::Variable(_) => Precedence::ATOMIC,
+            Self::Defined(_, _) => Precedence::ATOMIC,
             Self::Conjonction(_, _) => Precedence::CONJONCTION,
             Self::Disjonction(_, _) => Precedence::DISJONCTION,
             Self::Implication(_, lhs) => {
@@ -58,15 +107,43 @@ impl Prop {
     }
 }
 
+/// Index of the first `c` outside of parentheses, to split lists of
+/// formulas whose connectives take several arguments, eg: `Xor(A, B), C`
+pub(crate) fn find_outside_parentheses(s: &str, c: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, d) in s.char_indices() {
+        match d {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            d if d == c && depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the lexer reads the name as a variable: `[A-Za-z][A-Za-z0-9_]*`
+pub(crate) fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether the lexer reads the name as a connective, possibly namespaced,
+/// eg: `basics.Xor`
+pub(crate) fn is_connective_name(name: &str) -> bool {
+    name.split('.').all(is_variable_name)
+}
+
 #[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
-struct Precedence(usize);
+pub(crate) struct Precedence(usize);
 impl Precedence {
-    const ATOMIC: Self = Self(0);
-    const NEGATION: Self = Self(1);
-    const CONJONCTION: Self = Self(2);
-    const DISJONCTION: Self = Self(3);
-    const IMPLICATION: Self = Self(4);
-    const EQUIVALENCE: Self = Self(5);
+    pub(crate) const ATOMIC: Self = Self(0);
+    pub(crate) const NEGATION: Self = Self(1);
+    pub(crate) const CONJONCTION: Self = Self(2);
+    pub(crate) const DISJONCTION: Self = Self(3);
+    pub(crate) const IMPLICATION: Self = Self(4);
+    pub(crate) const EQUIVALENCE: Self = Self(5);
 }
 
 #[derive(Clone)]
@@ -87,14 +164,20 @@ impl<'a> PropRepr<'a> {
         self.conf.negation = true;
         self
     }
+    /// Width of the representation on the screen
     pub fn len(&self) -> usize {
         let mut repr = self.clone();
         repr.conf.formated = false;
-        repr.to_string().len()
+        repr.to_string().chars().count()
     }
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// Same representation, broken on several lines at the weakest
+    /// connectives to fit in `width` columns when possible
+    pub fn pretty(&self, width: usize) -> String {
+        pretty::prop_doc(self.prop, self.conf).pretty(width)
+    }
     fn precedence(&self) -> Precedence {
         self.prop.precedence(self.conf.negation)
     }
@@ -185,178 +268,427 @@ impl<'a> fmt::Display for PropRepr<'a> {
                 self.precedence(),
                 symbols::Sym::Equivalence,
             ),
+            Prop::Defined(name, args) => {
+                name.fmt(f)?;
+                symbols::Sym::OpenParenthesis.fmt(self.conf, f)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        symbols::Sym::Comma.fmt(self.conf, f)?;
+                        " ".fmt(f)?;
+                    }
+                    arg.repr_conf(self.conf).fmt(f)?;
+                }
+                symbols::Sym::CloseParenthesis.fmt(self.conf, f)
+            }
         }
     }
 }
 
+/// Maximum depth of a parsed property, deeper properties would overflow
+/// the stack of the recursive functions working on them
+pub(crate) const MAX_DEPTH: usize = 1024;
+
 mod parser {
-    use super::Prop;
+    use super::{Precedence, Prop, MAX_DEPTH};
+    use crate::error::ParseError;
+    use std::ops::Range;
 
+    /// Reads a property written with ascii or unicode symbols, eg: `A/\\B->~C`
+    /// or `A∧B→¬C`, the ascii alternates `&`, `|`, `=>` and `<=>` are also
+    /// accepted. The output of `PropRepr` can always be read back, whatever
+    /// its `ReprConf`, formating escape sequences are ignored.
     impl std::str::FromStr for Prop {
-        type Err = &'static str;
+        type Err = ParseError;
         fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let v: Vec<_> = Lexer::new(&mut s.chars()).collect::<Result<_, _>>()?;
-            syntax_parse(&v)
+            syntax_parse(s, &lex(s)?)
         }
     }
 
     #[derive(Debug)]
     enum LexItem {
-        Parenthesized(Vec<Self>), // ( .* )
-        False,                    // !
-        Name(String),             // [A-Za-z][A-Za-z0-9_]*
-        Conjonction,              // /\
-        Disjonction,              // \/
-        Implication,              // ->
-        Equivalence,              // <->
-        Negation,                 // ~
+        Open,         // (
+        Close,        // )
+        False,        // ! ⊥
+        True,         // ⊤
+        Name(String), // [A-Za-z][A-Za-z0-9_]*
+        Apply(String), // [A-Za-z][A-Za-z0-9_]*(, or namespaced: basics.Xor(
+        Comma,        // ,
+        Conjonction,  // /\ ∧ &
+        Disjonction,  // \/ ∨ |
+        Implication,  // -> → ➔ =>
+        Equivalence,  // <-> ↔ <=>
+        Negation,     // ~ ¬
+    }
+
+    #[derive(Debug)]
+    struct Lexeme {
+        item: LexItem,
+        span: Range<usize>,
     }
 
-    struct Parenthesized<'a> {
-        input: &'a mut dyn Iterator<Item = char>,
-        level: usize,
+    type Input<'a> = std::iter::Peekable<std::str::CharIndices<'a>>;
+
+    fn must_follow(input: &mut Input<'_>, end: usize, c: char, expected: &'static str) -> Result<(), ParseError> {
+        match input.next() {
+            Some((_, n)) if n == c => Ok(()),
+            Some((i, n)) => Err(ParseError::new(i..i + n.len_utf8(), "unexpected character")
+                .expected(&[expected])
+                .found(&n.to_string())),
+            None => Err(ParseError::new(end..end, "unexpected end of stream")
+                .expected(&[expected])
+                .found("")),
+        }
     }
-    impl<'a> Parenthesized<'a> {
-        fn new(input: &'a mut impl Iterator<Item = char>) -> Self {
-            Self { input, level: 0 }
+
+    /// Skips the complete escape sequences at the start of the input
+    fn skip_escapes(input: &mut Input<'_>) {
+        loop {
+            let mut ahead = input.clone();
+            if ahead.next_if(|(_, c)| *c == '\x1b').is_none() || ahead.next_if(|(_, c)| *c == '[').is_none() {
+                return;
+            }
+            while ahead.next_if(|(_, c)| matches!(c, ' '..='?')).is_some() {}
+            if ahead.next_if(|(_, c)| matches!(c, '@'..='~')).is_none() {
+                return;
+            }
+            *input = ahead;
         }
     }
-    impl<'a> Iterator for Parenthesized<'a> {
-        type Item = Result<char, ()>;
-        fn next(&mut self) -> Option<Self::Item> {
-            if let Some(c) = self.input.next() {
-                match c {
-                    '(' => {
-                        self.level += 1;
-                        Some(Ok(c))
+
+    /// Splits the input in lexemes, in a single pass
+    fn lex(src: &str) -> Result<Vec<Lexeme>, ParseError> {
+        let end = src.len();
+        let input = &mut src.char_indices().peekable();
+        let mut lexemes = Vec::new();
+        while let Some((i, c)) = input.next() {
+            let item = match c {
+                '(' => LexItem::Open,
+                ')' => LexItem::Close,
+                ',' => LexItem::Comma,
+                '!' | '⊥' => LexItem::False,
+                '⊤' => LexItem::True,
+                '~' | '¬' => LexItem::Negation,
+                '∧' | '&' => LexItem::Conjonction,
+                '∨' | '|' => LexItem::Disjonction,
+                '→' | '➔' => LexItem::Implication,
+                '↔' => LexItem::Equivalence,
+                '/' => must_follow(input, end, '\\', "/\\").map(|()| LexItem::Conjonction)?,
+                '\\' => must_follow(input, end, '/', "\\/").map(|()| LexItem::Disjonction)?,
+                '-' => must_follow(input, end, '>', "->").map(|()| LexItem::Implication)?,
+                '=' => must_follow(input, end, '>', "=>").map(|()| LexItem::Implication)?,
+                '<' => match input.next_if(|(_, c)| *c == '=') {
+                    Some(_) => must_follow(input, end, '>', "<=>"),
+                    None => must_follow(input, end, '-', "<->")
+                        .and_then(|()| must_follow(input, end, '>', "<->")),
+                }
+                .map(|()| LexItem::Equivalence)?,
+                '\x1b' => {
+                    // skips the escape sequences written by formated representations
+                    must_follow(input, end, '[', "\x1b[")?;
+                    while input.next_if(|(_, c)| matches!(c, ' '..='?')).is_some() {}
+                    if input.next_if(|(_, c)| matches!(c, '@'..='~')).is_none() {
+                        return Err(ParseError::new(i..i + 1, "unterminated escape sequence"));
                     }
-                    ')' if self.level == 0 => None,
-                    ')' => {
-                        self.level -= 1;
-                        Some(Ok(c))
+                    continue;
+                }
+                c @ 'A'..='Z' | c @ 'a'..='z' => {
+                    let mut name = String::new();
+                    name.push(c);
+                    loop {
+                        while let Some((_, c)) =
+                            input.next_if(|(_, c)| matches!(c, 'A'..='Z'|'a'..='z'|'_'|'0'..='9'))
+                        {
+                            name.push(c);
+                        }
+                        // connectives of imported files are namespaced, eg: `basics.Xor(A, B)`
+                        let mut ahead = input.clone();
+                        match (ahead.next(), ahead.next()) {
+                            (Some((_, '.')), Some((_, c))) if c.is_ascii_alphabetic() => {
+                                input.next();
+                                name.push('.');
+                            }
+                            _ => break,
+                        }
+                    }
+                    // formated representations may style the parenthesis
+                    let mut ahead = input.clone();
+                    skip_escapes(&mut ahead);
+                    match ahead.next_if(|(_, c)| *c == '(') {
+                        Some(_) => {
+                            *input = ahead;
+                            LexItem::Apply(name)
+                        }
+                        None if name.contains('.') => {
+                            let next = input.peek().map(|(i, _)| *i).unwrap_or(end);
+                            return Err(ParseError::new(i..next, "only defined connectives have a namespace")
+                                .found(&name));
+                        }
+                        None => LexItem::Name(name),
                     }
-                    c => Some(Ok(c)),
                 }
-            } else {
-                Some(Err(()))
-            }
+                c if c.is_whitespace() => continue,
+                c => {
+                    return Err(ParseError::new(i..i + c.len_utf8(), "unexpected character")
+                        .found(&c.to_string()))
+                }
+            };
+            let next = input.peek().map(|(i, _)| *i).unwrap_or(end);
+            lexemes.push(Lexeme { item, span: i..next });
         }
+        Ok(lexemes)
     }
-    struct Lexer<'a> {
-        input: std::iter::Peekable<&'a mut dyn Iterator<Item = char>>,
+
+    /// Operators waiting for their operands, with the span of their lexeme
+    enum Pending {
+        Open(Range<usize>),
+        /// Application of a defined connective, with its number of arguments
+        /// after the first one
+        Apply(Range<usize>, usize),
+        Negation(Range<usize>),
+        Binary(Precedence, Range<usize>),
     }
-    impl<'a> Lexer<'a> {
-        fn new(input: &'a mut impl Iterator<Item = char>) -> Self {
-            Self {
-                input: (input as &mut dyn Iterator<Item = char>).peekable(),
+
+    /// Applies the operator on top of the stack to its operands, which are
+    /// stored with their depth
+    fn reduce(
+        src: &str,
+        items: &[Lexeme],
+        pending: &mut Vec<(Pending, usize)>,
+        operands: &mut Vec<(Prop, usize)>,
+    ) -> Result<(), ParseError> {
+        let (span, prop, depth) = match pending.pop() {
+            Some((Pending::Negation(span), _)) => {
+                let (prop, depth) = operands.pop().expect("negation without operand");
+                (span, prop.negate(), depth + 1)
+            }
+            Some((Pending::Binary(_, span), index)) => {
+                let (rhs, rhs_depth) = operands.pop().expect("operator without right operand");
+                let (lhs, lhs_depth) = operands.pop().expect("operator without left operand");
+                let (lhs, rhs) = (Box::new(lhs), Box::new(rhs));
+                let prop = match items[index].item {
+                    LexItem::Conjonction => Prop::Conjonction(lhs, rhs),
+                    LexItem::Disjonction => Prop::Disjonction(lhs, rhs),
+                    LexItem::Implication => Prop::Implication(lhs, rhs),
+                    LexItem::Equivalence => Prop::Equivalence(lhs, rhs),
+                    _ => unreachable!(),
+                };
+                (span, prop, lhs_depth.max(rhs_depth) + 1)
             }
+            _ => unreachable!(),
+        };
+        if depth > MAX_DEPTH {
+            return Err(ParseError::new(span.clone(), "formula nested too deeply").found(&src[span]));
         }
-        fn must_follow(&mut self, c: char) -> Result<(), &'static str> {
-            if self.input.next().ok_or("unexpected end of stream")? == c {
-                Ok(())
-            } else {
-                Err("unexpected character")
+        operands.push((prop, depth));
+        Ok(())
+    }
+
+    /// Replaces the arguments on top of the operands by the application of
+    /// the connective named by `item`
+    fn apply(
+        src: &str,
+        item: &Lexeme,
+        span: Range<usize>,
+        commas: usize,
+        operands: &mut Vec<(Prop, usize)>,
+    ) -> Result<(), ParseError> {
+        let name = match &item.item {
+            LexItem::Apply(name) => name.clone(),
+            _ => unreachable!(),
+        };
+        let args = operands.split_off(operands.len() - commas - 1);
+        let depth = args.iter().map(|(_, depth)| *depth).max().unwrap_or(0) + 1;
+        if depth > MAX_DEPTH {
+            return Err(ParseError::new(span.clone(), "formula nested too deeply").found(&src[span]));
+        }
+        operands.push((Prop::Defined(name, args.into_iter().map(|(arg, _)| arg).collect()), depth));
+        Ok(())
+    }
+
+    /// Reduces the pending operators binding tighter than `preced`
+    fn reduce_tighter(
+        src: &str,
+        items: &[Lexeme],
+        pending: &mut Vec<(Pending, usize)>,
+        operands: &mut Vec<(Prop, usize)>,
+        preced: Precedence,
+    ) -> Result<(), ParseError> {
+        while let Some((Pending::Negation(_), _)) | Some((Pending::Binary(_, _), _)) = pending.last() {
+            match pending.last() {
+                Some((Pending::Binary(p, _), _)) if *p >= preced => break,
+                _ => reduce(src, items, pending, operands)?,
             }
         }
+        Ok(())
     }
-    impl<'a> Iterator for Lexer<'a> {
-        type Item = Result<LexItem, &'static str>;
-        fn next(&mut self) -> Option<Self::Item> {
-            Some(match self.input.next()? {
-                '(' => {
-                    let r: Result<Vec<char>, ()> = Parenthesized::new(&mut self.input).collect();
-                    match r {
-                        Err(()) => return Some(Err("closing parenthesis expected")),
-                        Ok(mut v) => {
-                            let r: Result<Vec<_>, _> = Lexer::new(&mut v.drain(..)).collect();
-                            match r {
-                                Err(e) => Err(e),
-                                Ok(v) => Ok(LexItem::Parenthesized(v)),
+
+    /// Precedence climbing with explicit stacks, binary operators are right
+    /// associative and the negation binds tighter than all of them
+    fn syntax_parse(src: &str, items: &[Lexeme]) -> Result<Prop, ParseError> {
+        let mut pending: Vec<(Pending, usize)> = Vec::new();
+        let mut operands: Vec<(Prop, usize)> = Vec::new();
+        let mut expect_operand = true;
+        let mut previous_end = 0;
+        for (index, Lexeme { item, span }) in items.iter().enumerate() {
+            let empty = || {
+                ParseError::new(previous_end..span.start, "empty expression").expected(&["formula"])
+            };
+            if expect_operand {
+                match item {
+                    LexItem::False => operands.push((Prop::False, 1)),
+                    LexItem::True => operands.push((Prop::False.negate(), 2)),
+                    LexItem::Name(name) => operands.push((Prop::Variable(name.clone()), 1)),
+                    LexItem::Open => pending.push((Pending::Open(span.clone()), index)),
+                    LexItem::Apply(_) => pending.push((Pending::Apply(span.clone(), 0), index)),
+                    LexItem::Negation => pending.push((Pending::Negation(span.clone()), index)),
+                    LexItem::Close if !pending.iter().any(|(p, _)| matches!(p, Pending::Open(_) | Pending::Apply(_, _))) => {
+                        return Err(ParseError::new(span.clone(), "unexpected closing parenthesis").found(")"))
+                    }
+                    _ => return Err(empty()),
+                }
+                expect_operand = matches!(item, LexItem::Open | LexItem::Apply(_) | LexItem::Negation);
+            } else {
+                let preced = match item {
+                    LexItem::Conjonction => Precedence::CONJONCTION,
+                    LexItem::Disjonction => Precedence::DISJONCTION,
+                    LexItem::Implication => Precedence::IMPLICATION,
+                    LexItem::Equivalence => Precedence::EQUIVALENCE,
+                    LexItem::Close => {
+                        reduce_tighter(src, items, &mut pending, &mut operands, Precedence(usize::MAX))?;
+                        match pending.pop() {
+                            None => {
+                                return Err(ParseError::new(span.clone(), "unexpected closing parenthesis").found(")"))
+                            }
+                            Some((Pending::Apply(open, commas), at)) => {
+                                apply(src, &items[at], open.start..span.end, commas, &mut operands)?
                             }
+                            Some(_) => {}
                         }
+                        previous_end = span.end;
+                        continue;
                     }
-                }
-                ')' => Err("unexpected closing parenthesis"),
-                '!' => Ok(LexItem::False),
-                '~' => Ok(LexItem::Negation),
-                '/' => self.must_follow('\\').map(|()| LexItem::Conjonction),
-                '\\' => self.must_follow('/').map(|()| LexItem::Disjonction),
-                '-' => self.must_follow('>').map(|()| LexItem::Implication),
-                '<' => self.must_follow('-').and(self.must_follow('>')).map(|()| LexItem::Equivalence),
-                c @ 'A'..='Z' | c @ 'a'..='z' => {
-                    let mut name = String::new();
-                    name.push(c);
-                    while self
-                        .input
-                        .peek()
-                        .map(|c| matches!(c, 'A'..='Z'|'a'..='z'|'_'|'0'..='9'))
-                        .unwrap_or(false)
-                    {
-                        name.push(self.input.next().unwrap());
+                    LexItem::Comma => {
+                        reduce_tighter(src, items, &mut pending, &mut operands, Precedence(usize::MAX))?;
+                        match pending.last_mut() {
+                            Some((Pending::Apply(_, commas), _)) => *commas += 1,
+                            _ => {
+                                return Err(ParseError::new(span.clone(), "unexpected comma outside of the arguments of a connective")
+                                    .found(","))
+                            }
+                        }
+                        previous_end = span.end;
+                        expect_operand = true;
+                        continue;
                     }
-                    Ok(LexItem::Name(name))
-                }
-                ' ' => self.next()?,
-                _ => Err("unexpected character"),
-            })
+                    LexItem::Negation => {
+                        return Err(ParseError::new(span.clone(), "negation is not a binary operator")
+                            .found(&src[span.clone()]))
+                    }
+                    _ => {
+                        return Err(ParseError::new(span.clone(), "operator not found")
+                            .expected(&["operator"])
+                            .found(&src[span.clone()]))
+                    }
+                };
+                reduce_tighter(src, items, &mut pending, &mut operands, preced)?;
+                pending.push((Pending::Binary(preced, span.clone()), index));
+                expect_operand = true;
+            }
+            previous_end = span.end;
         }
+        if expect_operand {
+            return Err(ParseError::new(previous_end..src.len(), "empty expression").expected(&["formula"]));
+        }
+        reduce_tighter(src, items, &mut pending, &mut operands, Precedence(usize::MAX))?;
+        if let Some((Pending::Open(span), _)) | Some((Pending::Apply(span, _), _)) = pending.pop() {
+            return Err(ParseError::new(span, "closing parenthesis expected")
+                .expected(&[")"])
+                .found(""));
+        }
+        Ok(operands.pop().expect("parsed property").0)
     }
-    fn syntax_parse(items: &[LexItem]) -> Result<Prop, &'static str> {
-        fn split_at_weak(items: &[LexItem]) -> Option<(&[LexItem], &LexItem, &[LexItem])> {
-            use super::Precedence;
-            let mut max_preced = None;
-            let mut index = None;
-            for (i, p) in items
-                .iter()
-                .map(|e| match e {
-                    LexItem::Conjonction => Some(Precedence::CONJONCTION),
-                    LexItem::Disjonction => Some(Precedence::DISJONCTION),
-                    LexItem::Implication => Some(Precedence::IMPLICATION),
-                    LexItem::Equivalence => Some(Precedence::EQUIVALENCE),
-                    LexItem::Negation => Some(Precedence::NEGATION),
-                    _ => None,
-                })
-                .enumerate()
-            {
-                if p > max_preced {
-                    max_preced = p;
-                    index = Some(i);
-                }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sequent;
+
+    fn every_repr_conf() -> impl Iterator<Item = symbols::ReprConf> {
+        (0..32).map(|bits| symbols::ReprConf {
+            negation: bits & 1 != 0,
+            formated: bits & 2 != 0,
+            unicode: bits & 4 != 0,
+            emphazis: bits & 8 != 0,
+            numbered: bits & 16 != 0,
+        })
+    }
+
+    fn reads_back_in_every_repr_conf(props: &[&str]) {
+        for prop in props {
+            let prop: Prop = prop.parse().unwrap();
+            for conf in every_repr_conf() {
+                let repr = prop.repr_conf(conf).to_string();
+                assert_eq!(repr.parse::<Prop>().ok(), Some(prop.clone()), "{:?}", repr);
             }
-            let (left, right) = items.split_at(index?);
-            let (op, right) = right.split_first()?;
-            Some((left, op, right))
         }
-        match items {
-            [] => Err("empty expression"),
-            [LexItem::False] => Ok(Prop::False),
-            [LexItem::Name(name)] => Ok(Prop::Variable(name.clone())),
-            [LexItem::Parenthesized(v)] => syntax_parse(v),
-            items => {
-                if let Some((left, op, right)) = split_at_weak(items) {
-                    if let LexItem::Negation = op {
-                        if let [] = left {
-                            Ok(syntax_parse(right)?.negate())
-                        } else {
-                            Err("negation is not a binary operator")
-                        }
-                    } else {
-                        let (left, right) = (
-                            Box::new(syntax_parse(left)?),
-                            Box::new(syntax_parse(right)?),
-                        );
-                        Ok(match op {
-                            LexItem::Conjonction => Prop::Conjonction(left, right),
-                            LexItem::Disjonction => Prop::Disjonction(left, right),
-                            LexItem::Implication => Prop::Implication(left, right),
-                            LexItem::Equivalence => Prop::Equivalence(left, right),
-                            _ => unreachable!(),
-                        })
-                    }
-                } else {
-                    Err("operator not found")
-                }
-            }
+    }
+
+    #[test]
+    fn connectives_read_back_in_every_repr_conf() {
+        reads_back_in_every_repr_conf(&[
+            "A /\\ B -> ~C \\/ !",
+            "~~(A <-> B) -> (C -> D) -> E",
+            "~(A -> !) /\\ (B \\/ C /\\ D)",
+            "~!",
+            "(A -> B) -> (~A -> ~B) <-> ~~(A <-> ~B)",
+        ]);
+    }
+
+    #[test]
+    fn sequents_read_back_in_every_repr_conf() {
+        let sequent: Sequent = "A -> B, ~B, C /\\ ~! |- ~A \\/ (B <-> C)".parse().unwrap();
+        for conf in every_repr_conf().filter(|conf| !conf.numbered) {
+            let repr = sequent.repr_conf(conf).to_string();
+            assert_eq!(repr.parse::<Sequent>().ok(), Some(sequent.clone()), "{:?}", repr);
+        }
+    }
+
+    #[test]
+    fn alternate_connectives() {
+        let prop: Prop = "(A -> ~B) <-> C /\\ D \\/ !".parse().unwrap();
+        for alternate in &["(A => ~B) <=> C & D | !", "(A → ¬B) ↔ C ∧ D ∨ ⊥", "(A ➔ ¬B) ↔ C∧D∨⊥"] {
+            assert_eq!(alternate.parse::<Prop>().ok(), Some(prop.clone()), "{:?}", alternate);
         }
+        assert_eq!("⊤".parse::<Prop>().ok(), Some(Prop::False.negate()));
+    }
+
+    #[test]
+    fn defined_connectives_read_back_in_every_repr_conf() {
+        reads_back_in_every_repr_conf(&[
+            "Xor(A, ~B) <-> Xor(Xor(A, B), C /\\ D)",
+            "~Not(Maj(A, B -> C, !))",
+        ]);
+    }
+
+    #[test]
+    fn depth_is_limited() {
+        let negations = |n: usize| format!("{}A", "~".repeat(n));
+        assert!(negations(MAX_DEPTH - 1).parse::<Prop>().is_ok());
+        let error = negations(MAX_DEPTH).parse::<Prop>().unwrap_err();
+        assert_eq!((error.message, error.span), ("formula nested too deeply", 0..1));
+        let nested = |n: usize| format!("{}A{}", "~(".repeat(n), ")".repeat(n));
+        assert!(nested(MAX_DEPTH - 1).parse::<Prop>().is_ok());
+        let error = nested(2 * MAX_DEPTH).parse::<Prop>().unwrap_err();
+        assert_eq!(error.message, "formula nested too deeply");
+        let implications = vec!["A"; MAX_DEPTH + 1].join(" -> ");
+        let error = implications.parse::<Prop>().unwrap_err();
+        assert_eq!(error.message, "formula nested too deeply");
+    }
+
+    #[test]
+    fn parentheses_dont_count() {
+        let parenthesized = format!("{}A{} /\\ B", "(".repeat(4 * MAX_DEPTH), ")".repeat(4 * MAX_DEPTH));
+        assert_eq!(parenthesized.parse::<Prop>().ok(), "A /\\ B".parse().ok());
     }
 }
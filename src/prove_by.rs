@@ -1,4 +1,7 @@
-use super::property::Prop;
+use super::error::{offset_in, ParseError};
+use super::property::{find_outside_parentheses, Prop};
+use super::symbols::ReprConf;
+use std::fmt;
 
 #[derive(Debug, Clone)]
 pub enum ProveBy {
@@ -14,10 +17,26 @@ pub enum ProveBy {
     Eliminate(usize),
     ConjonctionIntroduction,
     ConjonctionElimination(Prop, Prop),
+    ReductioAdAbsurdum,
+    /// Application of the lemma with this name, with the values of the
+    /// variables of its hypotheses that are not in its conclusion, see
+    /// `Proof::prove_with`
+    Use(String, Vec<(String, Prop)>),
+    /// Rewriting of the connective with this name to its body, in the
+    /// conclusion or in the hypothesis with this index
+    Unfold(String, Option<usize>),
+    /// Rewriting of the body of the connective with this name to its
+    /// applications, in the conclusion or in the hypothesis with this index
+    Fold(String, Option<usize>),
 }
 
+pub(crate) const RULES: &[&str] = &[
+    "h", "i", "xf", "e", "ii", "iis", "dil", "dir", "mp", "de", "ce", "ci", "raa", "use", "unfold",
+    "fold",
+];
+
 fn split_once(s: &str, c: char) -> (&str, &str) {
-    if let Some(i) = s.find(c) {
+    if let Some(i) = find_outside_parentheses(s, c) {
         let (l, r) = s.split_at(i);
         (l.trim(), r[1..].trim())
     } else {
@@ -26,67 +45,206 @@ fn split_once(s: &str, c: char) -> (&str, &str) {
     }
 }
 
-fn expect_end(s: &str) -> Result<(), &'static str> {
+fn expect_end(input: &str, s: &str) -> Result<(), ParseError> {
     if s.trim().is_empty() {
         Ok(())
     } else {
-        Err("unexpected argument")
+        let at = offset_in(input, s);
+        Err(ParseError::new(at..at + s.len(), "unexpected argument").found(s))
     }
 }
-fn parse_arg_prop(s: &str) -> Result<(Prop, &str), &'static str> {
+fn parse_arg_prop<'a>(input: &str, s: &'a str) -> Result<(Prop, &'a str), ParseError> {
     let (l, r) = split_once(s, ',');
-    Ok((l.parse()?, r))
+    Ok((
+        l.parse()
+            .map_err(|e: ParseError| e.offset(offset_in(input, l)))?,
+        r,
+    ))
+}
+/// Reads `VAR := FORMULA`
+fn parse_arg_value<'a>(input: &str, s: &'a str) -> Result<((String, Prop), &'a str), ParseError> {
+    let (var, rest) = s.split_once(":=").ok_or_else(|| {
+        let at = offset_in(input, s);
+        ParseError::new(at..at + s.trim_end().len(), "expecting the value of a variable")
+            .expected(&["VAR := FORMULA"])
+            .found(s.trim())
+    })?;
+    let var = var.trim();
+    match var.parse() {
+        Ok(Prop::Variable(name)) => {
+            let (value, rest) = parse_arg_prop(input, rest)?;
+            Ok(((name, value), rest))
+        }
+        _ => {
+            let at = offset_in(input, var);
+            Err(ParseError::new(at..at + var.len(), "not a variable").found(var))
+        }
+    }
 }
-fn parse_arg_num(s: &str) -> Result<(usize, &str), &'static str> {
+fn parse_arg_num<'a>(input: &str, s: &'a str) -> Result<(usize, &'a str), ParseError> {
     let (l, r) = split_once(s, ' ');
-    Ok((l.parse().map_err(|_| "not a number")?, r))
+    let at = offset_in(input, l);
+    Ok((
+        l.parse().map_err(|_| {
+            ParseError::new(at..at + l.len(), "not a number")
+                .expected(&["number"])
+                .found(l)
+        })?,
+        r,
+    ))
 }
 
 impl std::str::FromStr for ProveBy {
-    type Err = &'static str;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (name, args) = split_once(s, ' ');
         match name {
-            "h" => {expect_end(args)?; Ok(Self::Hypothesis)}
-            "ii" => {expect_end(args)?; Ok(Self::ImplicationIntroduction)}
-            "ci" => {expect_end(args)?; Ok(Self::ConjonctionIntroduction)}
-            "iis" => {expect_end(args)?; Ok(Self::ImplicationIntroductions)}
-            "i" => {expect_end(args)?; Ok(Self::Introduction)}
+            "h" => {expect_end(s, args)?; Ok(Self::Hypothesis)}
+            "ii" => {expect_end(s, args)?; Ok(Self::ImplicationIntroduction)}
+            "ci" => {expect_end(s, args)?; Ok(Self::ConjonctionIntroduction)}
+            "iis" => {expect_end(s, args)?; Ok(Self::ImplicationIntroductions)}
+            "i" => {expect_end(s, args)?; Ok(Self::Introduction)}
             "mp" => {
-                let (arg1, args) = parse_arg_prop(args)?;
-                expect_end(args)?;
+                let (arg1, args) = parse_arg_prop(s, args)?;
+                expect_end(s, args)?;
                 Ok(Self::ModusPonens(arg1))
             }
             "e" => {
-                let (arg1, args) = parse_arg_num(args)?;
-                expect_end(args)?;
+                let (arg1, args) = parse_arg_num(s, args)?;
+                expect_end(s, args)?;
                 Ok(Self::Eliminate(arg1))
             }
             "dil" => {
-                expect_end(args)?;
+                expect_end(s, args)?;
                 Ok(Self::DisjonctionIntroductionLeft)
             }
             "dir" => {
-                expect_end(args)?;
+                expect_end(s, args)?;
                 Ok(Self::DisjonctionIntroductionRight)
             }
             "de" => {
-                let (arg1, args) = parse_arg_prop(args)?;
-                let (arg2, args) = parse_arg_prop(args)?;
-                expect_end(args)?;
+                let (arg1, args) = parse_arg_prop(s, args)?;
+                let (arg2, args) = parse_arg_prop(s, args)?;
+                expect_end(s, args)?;
                 Ok(Self::DisjonctionElimination(arg1, arg2))
             }
             "ce" => {
-                let (arg1, args) = parse_arg_prop(args)?;
-                let (arg2, args) = parse_arg_prop(args)?;
-                expect_end(args)?;
+                let (arg1, args) = parse_arg_prop(s, args)?;
+                let (arg2, args) = parse_arg_prop(s, args)?;
+                expect_end(s, args)?;
                 Ok(Self::ConjonctionElimination(arg1, arg2))
             }
             "xf" => {
-                expect_end(args)?;
+                expect_end(s, args)?;
                 Ok(Self::Exfalso)
             }
-            _ => Err("unknown rule application "),
+            "raa" => {
+                expect_end(s, args)?;
+                Ok(Self::ReductioAdAbsurdum)
+            }
+            "use" => {
+                let (name, args) = split_once(args, ' ');
+                if name.is_empty() {
+                    let at = s.len();
+                    return Err(ParseError::new(at..at, "expecting the name of a lemma")
+                        .expected(&["name"])
+                        .found(""));
+                }
+                let mut values = Vec::new();
+                let mut args = args;
+                while !args.trim().is_empty() {
+                    let (value, rest) = parse_arg_value(s, args)?;
+                    values.push(value);
+                    args = rest;
+                }
+                Ok(Self::Use(name.to_owned(), values))
+            }
+            "unfold" | "fold" => {
+                let (arg1, args) = split_once(args, ' ');
+                if arg1.is_empty() {
+                    let at = s.len();
+                    return Err(ParseError::new(at..at, "expecting the name of a connective")
+                        .expected(&["name"])
+                        .found(""));
+                }
+                let at = if args.is_empty() {
+                    None
+                } else {
+                    let (index, args) = parse_arg_num(s, args)?;
+                    expect_end(s, args)?;
+                    Some(index)
+                };
+                if name == "unfold" {
+                    Ok(Self::Unfold(arg1.to_owned(), at))
+                } else {
+                    Ok(Self::Fold(arg1.to_owned(), at))
+                }
+            }
+            _ => {
+                let at = offset_in(s, name);
+                Err(ParseError::new(at..at + name.len(), "unknown rule application")
+                    .expected(RULES)
+                    .found(name))
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+impl ProveBy {
+    /// Names of all the rules, as written in commands
+    pub const NAMES: &'static [&'static str] = RULES;
+    /// Name of the rule, as written in commands
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hypothesis => "h",
+            Self::ImplicationIntroduction => "ii",
+            Self::Introduction => "i",
+            Self::ModusPonens(_) => "mp",
+            Self::DisjonctionIntroductionLeft => "dil",
+            Self::DisjonctionIntroductionRight => "dir",
+            Self::Exfalso => "xf",
+            Self::ImplicationIntroductions => "iis",
+            Self::DisjonctionElimination(_, _) => "de",
+            Self::Eliminate(_) => "e",
+            Self::ConjonctionIntroduction => "ci",
+            Self::ConjonctionElimination(_, _) => "ce",
+            Self::ReductioAdAbsurdum => "raa",
+            Self::Use(_, _) => "use",
+            Self::Unfold(_, _) => "unfold",
+            Self::Fold(_, _) => "fold",
+        }
+    }
+}
+
+impl fmt::Display for ProveBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conf = ReprConf {
+            negation: true,
+            ..Default::default()
+        };
+        self.name().fmt(f)?;
+        match self {
+            Self::ModusPonens(a) => write!(f, " {}", a.repr_conf(conf)),
+            Self::DisjonctionElimination(a, b) | Self::ConjonctionElimination(a, b) => {
+                write!(f, " {}, {}", a.repr_conf(conf), b.repr_conf(conf))
+            }
+            Self::Eliminate(index) => write!(f, " {}", index),
+            Self::Use(name, values) => {
+                write!(f, " {}", name)?;
+                for (i, (var, value)) in values.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{}:={}", separator, var, value.repr_conf(conf))?;
+                }
+                Ok(())
+            }
+            Self::Unfold(name, at) | Self::Fold(name, at) => {
+                write!(f, " {}", name)?;
+                match at {
+                    Some(index) => write!(f, " {}", index),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use super::error::{offset_in, ParseError};
+use super::proof::{Context, GoalPath, Proof};
+use super::prove_by::ProveBy;
+use super::sequent::Sequent;
+use super::symbols::ReprConf;
+use std::fmt;
+
+/// A sequent followed by the rules applied to prove it, one by line, eg:
+///
+/// ```text
+/// # modus ponens
+/// P->Q, P |- Q
+/// mp P
+/// h
+/// h
+/// ```
+///
+/// Empty lines and lines starting with `#` are ignored. A rule applied on
+/// another sequent than the next not proven one is prefixed with its path,
+/// eg: `@0.1 h`.
+#[derive(Debug, Clone)]
+pub struct Script {
+    sequent: Sequent,
+    steps: Vec<Step>,
+}
+
+/// A rule applied on the sequent at `goal`, or on the next not proven
+/// sequent when `None`
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub goal: Option<GoalPath>,
+    pub rule: ProveBy,
+}
+impl Step {
+    /// Applies the rule on the proof, returns whether it could be applied
+    pub fn apply(&self, proof: &mut Proof) -> bool {
+        self.apply_with(proof, Context::default())
+    }
+    /// Same as `apply`, with the lemmas and the definitions the rule may
+    /// refer to
+    pub fn apply_with(&self, proof: &mut Proof, context: Context<'_>) -> bool {
+        match &self.goal {
+            Some(goal) => proof.prove_at_with(goal, self.rule.clone(), context),
+            None => match proof.next_not_proven_mut() {
+                Some(next) => next.prove_with(self.rule.clone(), context),
+                None => false,
+            },
+        }
+    }
+}
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(goal) = &self.goal {
+            write!(f, "@{} ", goal)?;
+        }
+        self.rule.fmt(f)
+    }
+}
+impl std::str::FromStr for Step {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (goal, rule) = match s.strip_prefix('@') {
+            Some(rest) => {
+                let (goal, rule) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let goal = goal.parse().map_err(|e: ParseError| e.offset(1))?;
+                (Some(goal), rule.trim_start())
+            }
+            None => (None, s),
+        };
+        let rule = rule.parse().map_err(|e: ParseError| e.offset(offset_in(s, rule)))?;
+        Ok(Self { goal, rule })
+    }
+}
+impl Script {
+    pub fn new(sequent: Sequent) -> Self {
+        Self {
+            sequent,
+            steps: Vec::new(),
+        }
+    }
+    pub fn sequent(&self) -> &Sequent {
+        &self.sequent
+    }
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+    /// Records a rule application on the next not proven sequent at the end
+    /// of the script
+    pub fn push(&mut self, p: ProveBy) {
+        self.steps.push(Step { goal: None, rule: p });
+    }
+    /// Records a rule application on the sequent at `goal` at the end of the
+    /// script
+    pub fn push_at(&mut self, goal: GoalPath, p: ProveBy) {
+        self.steps.push(Step {
+            goal: Some(goal),
+            rule: p,
+        });
+    }
+    /// Forgets the last recorded rule application
+    pub fn pop(&mut self) -> Option<Step> {
+        self.steps.pop()
+    }
+    /// Forgets all recorded rule applications
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let conf = ReprConf {
+            negation: true,
+            ..Default::default()
+        };
+        writeln!(f, "{}", self.sequent.repr_conf(conf))?;
+        for step in &self.steps {
+            writeln!(f, "{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Error while reading a script, `line` starts at 1 and the span of the
+/// error is relative to the whole script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub error: ParseError,
+}
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::str::FromStr for Script {
+    type Err = ScriptError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+        let error = |line: usize, l: &str| {
+            let at = offset_in(s, l);
+            move |e: ParseError| ScriptError {
+                line,
+                error: e.offset(at),
+            }
+        };
+        let mut script = match lines.next() {
+            Some((line, l)) => Self::new(l.parse().map_err(error(line, l))?),
+            None => {
+                return Err(ScriptError {
+                    line: 1,
+                    error: ParseError::new(0..0, "expecting a sequent").found(""),
+                })
+            }
+        };
+        for (line, l) in lines {
+            script.steps.push(l.parse().map_err(error(line, l))?);
+        }
+        Ok(script)
+    }
+}
+
+/// Error while replaying a script, `step` starts at 0
+#[derive(Debug, Clone)]
+pub struct ReplayError {
+    pub step: usize,
+    pub goal: Option<GoalPath>,
+    pub rule: Box<ProveBy>,
+    pub message: &'static str,
+    /// The proof as it was before the failing step
+    pub proof: Box<Proof>,
+}
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(goal) = &self.goal {
+            write!(f, "step {} `@{} {}`: {}", self.step + 1, goal, self.rule, self.message)
+        } else {
+            write!(f, "step {} `{}`: {}", self.step + 1, self.rule, self.message)
+        }
+    }
+}
+
+impl Proof {
+    /// Rebuilds a proof by applying each step of the script on its sequent,
+    /// the resulting proof may still be incomplete
+    pub fn replay(script: &Script) -> Result<Self, ReplayError> {
+        Self::replay_with(script, Context::default())
+    }
+    /// Same as `replay`, with the lemmas and the definitions the rules may
+    /// refer to
+    pub fn replay_with(script: &Script, context: Context<'_>) -> Result<Self, ReplayError> {
+        let mut proof = Self::not_proven(script.sequent.clone());
+        for (i, step) in script.steps.iter().enumerate() {
+            let open = match &step.goal {
+                Some(goal) => proof.goal(goal).map(|p| p.rule().is_none()).unwrap_or(false),
+                None => proof.next_not_proven().is_some(),
+            };
+            let message = if !open && step.goal.is_some() {
+                "no such sequent left to prove"
+            } else if !open {
+                "no sequent left to prove"
+            } else if !step.apply_with(&mut proof, context) {
+                match &step.rule {
+                    ProveBy::Use(name, _) if context.lemma(name).is_none() => "unknown lemma",
+                    ProveBy::Unfold(name, _) | ProveBy::Fold(name, _) if context.definition(name).is_none() => {
+                        "unknown definition"
+                    }
+                    _ => "can't apply rule",
+                }
+            } else {
+                continue;
+            };
+            return Err(ReplayError {
+                step: i,
+                goal: step.goal.clone(),
+                rule: step.rule.clone().into(),
+                message,
+                proof: proof.into(),
+            });
+        }
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_a_written_script() {
+        let mut script = Script::new("P->Q, P |- Q".parse().unwrap());
+        for rule in ["mp P", "h", "h"] {
+            script.push(rule.parse().unwrap());
+        }
+        let read: Script = script.to_string().parse().unwrap();
+        assert_eq!(read.to_string(), script.to_string());
+        let proof = Proof::replay(&read).unwrap();
+        assert!(proof.is_complete());
+        assert_eq!(crate::check(&proof), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_first_failing_step() {
+        let script: Script = "# modus ponens\nP->Q, P |- Q\nmp P\nh\nii\nh\n".parse().unwrap();
+        let error = Proof::replay(&script).unwrap_err();
+        assert_eq!((error.step, error.message), (2, "can't apply rule"));
+        assert_eq!(error.to_string(), "step 3 `ii`: can't apply rule");
+        assert_eq!(error.proof.open_goals().len(), 1);
+        let error = "P |- P\nh\nfoo\n".parse::<Script>().unwrap_err();
+        assert_eq!(error.line, 3);
+    }
+}
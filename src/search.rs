@@ -0,0 +1,164 @@
+use super::problem::Problem;
+use super::proof::Proof;
+use super::property::Prop;
+use super::prove_by::ProveBy;
+use super::sequent::Sequent;
+use std::collections::HashMap;
+
+/// Number of sequents the search tries before giving up
+const MAX_STEPS: usize = 100_000;
+
+/// Smallest proof of the problem, with at most `max_size` rules and only the
+/// rules it allows, `None` if there is none or if the search gives up
+pub fn search(problem: &Problem, max_size: usize) -> Option<Proof> {
+    let mut search = Search {
+        problem,
+        known: HashMap::new(),
+        steps: 0,
+    };
+    search.smallest(&problem.sequent, max_size)
+}
+
+struct Search<'a> {
+    problem: &'a Problem,
+    /// Smallest proof of each sequent, or the size up to which there is none
+    known: HashMap<Sequent, Result<Proof, usize>>,
+    steps: usize,
+}
+
+fn subformulas<'a>(prop: &'a Prop, formulas: &mut Vec<&'a Prop>) {
+    if formulas.contains(&prop) {
+        return;
+    }
+    formulas.push(prop);
+    match prop {
+        Prop::False | Prop::Variable(_) => {}
+        Prop::Conjonction(lhs, rhs)
+        | Prop::Disjonction(lhs, rhs)
+        | Prop::Implication(lhs, rhs)
+        | Prop::Equivalence(lhs, rhs) => {
+            subformulas(lhs, formulas);
+            subformulas(rhs, formulas);
+        }
+        Prop::Defined(_, args) => args.iter().for_each(|arg| subformulas(arg, formulas)),
+    }
+}
+
+impl<'a> Search<'a> {
+    /// Rules worth trying on the sequent, the formulas given to `mp`, `de`
+    /// and `ce` are taken from its hypotheses
+    fn rules(&self, s: &Sequent) -> Vec<ProveBy> {
+        let mut rules = vec![ProveBy::Hypothesis, ProveBy::Introduction];
+        if !self.problem.allows(&ProveBy::Introduction) {
+            rules.push(ProveBy::ImplicationIntroduction);
+            rules.push(ProveBy::ConjonctionIntroduction);
+        }
+        rules.push(ProveBy::DisjonctionIntroductionLeft);
+        rules.push(ProveBy::DisjonctionIntroductionRight);
+        rules.extend((0..s.hypotheses().len()).map(ProveBy::Eliminate));
+        let mut formulas = Vec::new();
+        s.hypotheses().iter().for_each(|h| subformulas(h, &mut formulas));
+        for prop in &formulas {
+            match prop {
+                Prop::Implication(lhs, rhs) if rhs.as_ref() == s.conclusion() => {
+                    rules.push(ProveBy::ModusPonens(lhs.as_ref().clone()))
+                }
+                Prop::Disjonction(lhs, rhs) if !s.hypotheses().contains(prop) => rules.push(
+                    ProveBy::DisjonctionElimination(lhs.as_ref().clone(), rhs.as_ref().clone()),
+                ),
+                Prop::Conjonction(lhs, rhs) if !s.hypotheses().contains(prop) => rules.push(
+                    ProveBy::ConjonctionElimination(lhs.as_ref().clone(), rhs.as_ref().clone()),
+                ),
+                _ => {}
+            }
+        }
+        if formulas.contains(&&Prop::False) && s.conclusion() != &Prop::False {
+            rules.push(ProveBy::Exfalso);
+        }
+        if s.conclusion() != &Prop::False {
+            rules.push(ProveBy::ReductioAdAbsurdum);
+        }
+        rules.retain(|rule| self.problem.allows(rule));
+        rules
+    }
+    /// Smallest proof of the sequent with at most `limit` rules
+    fn smallest(&mut self, s: &Sequent, limit: usize) -> Option<Proof> {
+        match self.known.get(s) {
+            Some(Ok(proof)) if proof.size() <= limit => return Some(proof.clone()),
+            Some(Ok(_)) => return None,
+            Some(Err(none_up_to)) if *none_up_to >= limit => return None,
+            _ => {}
+        }
+        if limit == 0 || self.steps >= MAX_STEPS {
+            return None;
+        }
+        self.steps += 1;
+        let mut best: Option<Proof> = None;
+        for rule in self.rules(s) {
+            let bound = best.as_ref().map_or(limit, |best| best.size() - 1);
+            if bound == 0 {
+                break;
+            }
+            let mut proof = Proof::not_proven(s.clone());
+            if !proof.prove_by(rule) {
+                continue;
+            }
+            // each premise needs at least one rule
+            let premises = proof.open_goals();
+            let mut size = 1 + premises.len();
+            if size > bound {
+                continue;
+            }
+            let mut proven = true;
+            for path in premises {
+                let goal = proof.goal_mut(&path).unwrap();
+                let sequent = goal.sequent().clone();
+                match self.smallest(&sequent, bound + 1 - size) {
+                    Some(p) => {
+                        size += p.size() - 1;
+                        *goal = p;
+                    }
+                    None => {
+                        proven = false;
+                        break;
+                    }
+                }
+            }
+            if proven {
+                best = Some(proof);
+            }
+        }
+        // a search that gave up may have missed the smallest proof, or any
+        if self.steps < MAX_STEPS {
+            self.known.insert(s.clone(), best.clone().ok_or(limit));
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(s: &str) -> Problem {
+        Problem::new(s.parse().unwrap())
+    }
+
+    #[test]
+    fn finds_the_smallest_proof() {
+        let proof = search(&problem("A, B |- A /\\ B"), 10).unwrap();
+        assert_eq!(proof.size(), 3);
+        assert!(proof.is_complete());
+        assert!(search(&problem("A, B |- A /\\ B"), 2).is_none());
+        assert!(search(&problem("A |- B"), 10).is_none());
+    }
+
+    #[test]
+    fn uses_only_the_allowed_rules() {
+        let mut p = problem("|- ~~A -> A");
+        assert!(search(&p, 10).is_none());
+        p.logic = crate::Logic::Classical;
+        let proof = search(&p, 10).unwrap();
+        assert_eq!(crate::check(&proof), Ok(()));
+    }
+}
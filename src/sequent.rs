@@ -1,8 +1,10 @@
-use super::property::Prop;
+use super::error::{offset_in, ParseError};
+use super::pretty;
+use super::property::{find_outside_parentheses, Prop};
 use super::symbols;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Sequent {
     hypotheses: Vec<Prop>,
     conclusion: Prop,
@@ -14,10 +16,10 @@ impl Sequent {
             conclusion,
         }
     }
-    pub fn repr(&self) -> SequentRepr {
+    pub fn repr(&self) -> SequentRepr<'_> {
         self.repr_conf(Default::default())
     }
-    pub fn repr_conf(&self, conf: symbols::ReprConf) -> SequentRepr {
+    pub fn repr_conf(&self, conf: symbols::ReprConf) -> SequentRepr<'_> {
         SequentRepr {
             sequent: self,
             conf,
@@ -49,17 +51,53 @@ impl<'a> SequentRepr<'a> {
         self.conf.negation = true;
         self
     }
+    /// One hypothesis by line with its index, as a goal panel
+    pub fn numbered(mut self) -> Self {
+        self.conf.numbered = true;
+        self
+    }
+    /// Width of the representation on the screen
     pub fn len(&self) -> usize {
         let mut repr = self.clone();
         repr.conf.formated = false;
-        repr.to_string().len()
+        repr.to_string().lines().map(|line| line.chars().count()).max().unwrap_or(0)
     }
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// Same representation, with one hypothesis by line and formulas broken
+    /// at their weakest connectives to fit in `width` columns when possible
+    pub fn pretty(&self, width: usize) -> String {
+        self.lines(width).into_iter().map(|(line, _)| line).collect::<Vec<_>>().join("\n")
+    }
+    /// Lines of `pretty` with their width on the screen
+    pub(crate) fn lines(&self, width: usize) -> Vec<(String, usize)> {
+        pretty::sequent_doc(self.sequent, self.conf).render(width)
+    }
+}
+impl<'a> SequentRepr<'a> {
+    fn fmt_numbered(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use fmt::Display;
+        let mut conf = self.conf;
+        conf.numbered = false;
+        let sequent = symbols::Sym::Sequent.repr(conf.unicode);
+        let count = self.sequent.hypotheses.len();
+        let width = count.saturating_sub(1).to_string().len().max(sequent.chars().count());
+        for (i, h) in self.sequent.hypotheses.iter().enumerate() {
+            writeln!(f, "{:>width$}  {}", i, h.repr_conf(conf), width = width)?;
+        }
+        write!(f, "{:>width$}", "", width = width - sequent.chars().count())?;
+        symbols::Sym::Sequent.fmt(conf, f)?;
+        "  ".fmt(f)?;
+        conf.emphazis = true;
+        self.sequent.conclusion.repr_conf(conf).fmt(f)
+    }
 }
 impl<'a> fmt::Display for SequentRepr<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.conf.numbered {
+            return self.fmt_numbered(f);
+        }
         let mut first = true;
         for h in &self.sequent.hypotheses {
             if !first {
@@ -80,23 +118,61 @@ impl<'a> fmt::Display for SequentRepr<'a> {
     }
 }
 
+fn parse_part(s: &str, part: &str) -> Result<Prop, ParseError> {
+    part.parse()
+        .map_err(|e: ParseError| e.offset(offset_in(s, part)))
+}
+
+/// Finds the first sequent symbol, in its ascii or unicode representation
+fn find_sequent_symbol(s: &str) -> Option<(usize, usize)> {
+    let sym = symbols::Sym::Sequent;
+    [sym.lex(), sym.repr(true)]
+        .iter()
+        .filter_map(|lex| s.find(lex).map(|i| (i, lex.len())))
+        .min()
+}
+
+/// Whether the string only holds spaces and formating escape sequences
+fn is_blank(s: &str) -> bool {
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                chars.next();
+                chars.by_ref().find(|c| matches!(c, '@'..='~'));
+            }
+            c if c.is_whitespace() => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Reads the output of any `SequentRepr`, see `Prop::from_str`
 impl std::str::FromStr for Sequent {
-    type Err = &'static str;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(symbols::Sym::Sequent.lex());
-        match [iter.next(), iter.next(), iter.next()] {
-            [Some(left), Some(right), None] => {
-                let hypotheses: Vec<_> = if left.trim().is_empty() {
-                    Vec::new()
-                } else {
-                    left.split(symbols::Sym::Comma.lex())
-                        .map(|h| h.parse())
-                        .collect::<Result<_, _>>()?
-                };
-                Ok(Self::new(hypotheses, right.parse()?))
-            }
-            [Some(prop), None, None] => Ok(Self::new(Vec::new(), prop.parse()?)),
-            _ => Err("expecting only one sequent symbol"),
+        let (left, right) = match find_sequent_symbol(s) {
+            Some((i, len)) => (&s[..i], &s[i + len..]),
+            None => return Ok(Self::new(Vec::new(), parse_part(s, s)?)),
+        };
+        if let Some((i, len)) = find_sequent_symbol(right) {
+            let at = offset_in(s, right) + i;
+            return Err(ParseError::new(at..at + len, "expecting only one sequent symbol")
+                .found(&s[at..at + len]));
         }
+        let hypotheses: Vec<_> = if is_blank(left) {
+            Vec::new()
+        } else {
+            let mut hypotheses = Vec::new();
+            let mut rest = left;
+            while let Some(i) = find_outside_parentheses(rest, ',') {
+                hypotheses.push(parse_part(s, &rest[..i])?);
+                rest = &rest[i + 1..];
+            }
+            hypotheses.push(parse_part(s, rest)?);
+            hypotheses
+        };
+        Ok(Self::new(hypotheses, parse_part(s, right)?))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,381 @@
+use super::error::ParseError;
+use super::property::{is_connective_name, is_variable_name, Prop, MAX_DEPTH};
+use super::sequent::Sequent;
+use std::fmt;
+use std::ops::Range;
+
+/// Displays a property or a sequent as an s-expression, eg:
+/// `(sequent (hyps (imply "P" "Q") "P") "Q")`
+pub struct SexprRepr<'a, T>(&'a T);
+
+impl Prop {
+    /// Wraps a property to display it as an s-expression, eg:
+    /// `(imply (and "P" "Q") (not "R"))`
+    pub fn sexpr(&self) -> SexprRepr<'_, Self> {
+        SexprRepr(self)
+    }
+    /// Reads a property written as an s-expression
+    pub fn from_sexpr(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s)?;
+        let prop = parser.prop()?;
+        parser.end()?;
+        Ok(prop)
+    }
+}
+
+impl Sequent {
+    /// Wraps a sequent to display it as an s-expression, eg:
+    /// `(sequent (hyps "P" "Q") (and "P" "Q"))`
+    pub fn sexpr(&self) -> SexprRepr<'_, Self> {
+        SexprRepr(self)
+    }
+    /// Reads a sequent written as an s-expression, a single property is read
+    /// as a sequent without hypotheses
+    pub fn from_sexpr(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s)?;
+        let sequent = if parser.is_form("sequent") {
+            parser.next();
+            parser.next();
+            parser.open()?;
+            parser.keyword("hyps")?;
+            let mut hypotheses = Vec::new();
+            while !parser.is_close() {
+                hypotheses.push(parser.prop()?);
+            }
+            parser.close()?;
+            let conclusion = parser.prop()?;
+            parser.close()?;
+            Self::new(hypotheses, conclusion)
+        } else {
+            Self::new(Vec::new(), parser.prop()?)
+        };
+        parser.end()?;
+        Ok(sequent)
+    }
+}
+
+fn quoted(f: &mut fmt::Formatter<'_>, name: &str) -> fmt::Result {
+    use fmt::Display;
+    '"'.fmt(f)?;
+    for c in name.chars() {
+        if c == '"' || c == '\\' {
+            '\\'.fmt(f)?;
+        }
+        c.fmt(f)?;
+    }
+    '"'.fmt(f)
+}
+
+impl<'a> fmt::Display for SexprRepr<'a, Prop> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, lhs, rhs) = match self.0 {
+            Prop::False => return "false".fmt(f),
+            Prop::Variable(name) => return quoted(f, name),
+            Prop::Defined(name, args) => {
+                "(apply ".fmt(f)?;
+                quoted(f, name)?;
+                for arg in args {
+                    write!(f, " {}", arg.sexpr())?;
+                }
+                return ")".fmt(f);
+            }
+            Prop::Implication(lhs, rhs) if rhs.as_ref() == &Prop::False => {
+                return write!(f, "(not {})", lhs.sexpr());
+            }
+            Prop::Conjonction(lhs, rhs) => ("and", lhs, rhs),
+            Prop::Disjonction(lhs, rhs) => ("or", lhs, rhs),
+            Prop::Implication(lhs, rhs) => ("imply", lhs, rhs),
+            Prop::Equivalence(lhs, rhs) => ("equiv", lhs, rhs),
+        };
+        write!(f, "({} {} {})", name, lhs.sexpr(), rhs.sexpr())
+    }
+}
+
+impl<'a> fmt::Display for SexprRepr<'a, Sequent> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "(sequent (hyps".fmt(f)?;
+        for h in self.0.hypotheses() {
+            write!(f, " {}", h.sexpr())?;
+        }
+        write!(f, ") {})", self.0.conclusion().sexpr())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Str(String),
+    Atom(String),
+}
+
+fn tokenize(s: &str) -> Result<Vec<(Token, Range<usize>)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut input = s.char_indices().peekable();
+    while let Some((start, c)) = input.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            ';' => {
+                while input.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '"' => {
+                let mut name = String::new();
+                loop {
+                    match input.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => name.extend(input.next().map(|(_, c)| c)),
+                        Some((_, c)) => name.push(c),
+                        None => {
+                            return Err(ParseError::new(start..start + 1, "unterminated string")
+                                .expected(&["\""])
+                                .found(""))
+                        }
+                    }
+                }
+                Token::Str(name)
+            }
+            c => {
+                let mut atom = String::new();
+                atom.push(c);
+                while let Some((_, c)) = input
+                    .next_if(|(_, c)| !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | ';'))
+                {
+                    atom.push(c);
+                }
+                Token::Atom(atom)
+            }
+        };
+        let end = input.peek().map(|(i, _)| *i).unwrap_or_else(|| s.len());
+        tokens.push((token, start..end));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(Token, Range<usize>)>,
+    index: usize,
+}
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Result<Self, ParseError> {
+        Ok(Self {
+            src,
+            tokens: tokenize(src)?,
+            index: 0,
+        })
+    }
+    fn peek_at(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.index + n).map(|(token, _)| token)
+    }
+    fn next(&mut self) -> Option<(Token, Range<usize>)> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+    fn is_form(&self, name: &str) -> bool {
+        self.peek_at(0) == Some(&Token::Open) && self.peek_at(1) == Some(&Token::Atom(name.to_owned()))
+    }
+    fn is_close(&self) -> bool {
+        matches!(self.peek_at(0), Some(Token::Close) | None)
+    }
+    /// Error on the token at `index`, or at the end of the input
+    fn error(&self, index: usize, message: &'static str) -> ParseError {
+        match self.tokens.get(index) {
+            Some((_, span)) => ParseError::new(span.clone(), message).found(&self.src[span.clone()]),
+            None => ParseError::new(self.src.len()..self.src.len(), message).found(""),
+        }
+    }
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), ParseError> {
+        if self.peek_at(0) == Some(&token) {
+            self.index += 1;
+            Ok(())
+        } else {
+            Err(self.error(self.index, "unexpected token").expected(&[expected]))
+        }
+    }
+    fn open(&mut self) -> Result<(), ParseError> {
+        self.expect(Token::Open, "(")
+    }
+    fn close(&mut self) -> Result<(), ParseError> {
+        self.expect(Token::Close, ")")
+    }
+    fn keyword(&mut self, keyword: &'static str) -> Result<(), ParseError> {
+        self.expect(Token::Atom(keyword.to_owned()), keyword)
+    }
+    fn end(&self) -> Result<(), ParseError> {
+        if self.index < self.tokens.len() {
+            Err(self.error(self.index, "unexpected token after the expression"))
+        } else {
+            Ok(())
+        }
+    }
+    /// Error on the token at `index` if a formula `depth` levels below the
+    /// `forms` being read is too deep
+    fn check_depth(&self, index: usize, forms: &[Form], depth: usize) -> Result<(), ParseError> {
+        if forms.len() + depth > MAX_DEPTH {
+            Err(self.error(index, "formula nested too deeply"))
+        } else {
+            Ok(())
+        }
+    }
+    /// Reads the operator of a form, after its opening parenthesis
+    fn operator(&mut self) -> Result<Operator, ParseError> {
+        let at = self.index;
+        match self.next() {
+            Some((Token::Atom(name), _)) => match name.as_str() {
+                "not" => Ok(Operator::Not),
+                "and" => Ok(Operator::And),
+                "or" => Ok(Operator::Or),
+                "imply" => Ok(Operator::Imply),
+                "equiv" => Ok(Operator::Equiv),
+                "apply" => {
+                    let at = self.index;
+                    match self.next() {
+                        Some((Token::Str(name), _)) if is_connective_name(&name) => Ok(Operator::Apply(name)),
+                        Some((Token::Str(_), _)) => {
+                            Err(self.error(at, "invalid connective name").expected(&["Name", "namespace.Name"]))
+                        }
+                        _ => Err(self
+                            .error(at, "expecting the name of a connective")
+                            .expected(&["\"name\""])),
+                    }
+                }
+                _ => Err(self.error(at, "unknown operator").expected(OPERATORS)),
+            },
+            _ => Err(self.error(at, "expecting an operator").expected(OPERATORS)),
+        }
+    }
+    /// Reads a formula with a stack of the forms being read rather than
+    /// recursively, so that deep formulas don't overflow the stack
+    fn prop(&mut self) -> Result<Prop, ParseError> {
+        let mut forms: Vec<Form> = Vec::new();
+        loop {
+            let prop = match forms.last() {
+                Some((operator, args)) if operator.arity().map_or(self.is_close(), |n| args.len() == n) => {
+                    self.close()?;
+                    let (operator, args) = forms.pop().expect("form on top of the stack");
+                    operator.apply(args)
+                }
+                _ => {
+                    let at = self.index;
+                    match self.next() {
+                        Some((Token::Str(name), _)) if is_variable_name(&name) => {
+                            self.check_depth(at, &forms, 1)?;
+                            Prop::Variable(name)
+                        }
+                        Some((Token::Str(_), _)) => {
+                            return Err(self
+                                .error(at, "invalid variable name")
+                                .expected(&["[A-Za-z][A-Za-z0-9_]*"]))
+                        }
+                        Some((Token::Atom(atom), _)) => match atom.as_str() {
+                            "false" => {
+                                self.check_depth(at, &forms, 1)?;
+                                Prop::False
+                            }
+                            "true" => {
+                                self.check_depth(at, &forms, 2)?;
+                                Prop::False.negate()
+                            }
+                            _ => {
+                                return Err(self
+                                    .error(at, "unknown atom, variables are written between quotes")
+                                    .expected(&["false", "true", "\"name\""]))
+                            }
+                        },
+                        Some((Token::Open, _)) => {
+                            self.check_depth(at, &forms, 1)?;
+                            let operator = self.operator()?;
+                            forms.push((operator, Vec::new()));
+                            continue;
+                        }
+                        _ => return Err(self.error(at, "expecting a formula").expected(&["formula"])),
+                    }
+                }
+            };
+            match forms.last_mut() {
+                Some((_, args)) => args.push(prop),
+                None => return Ok(prop),
+            }
+        }
+    }
+}
+
+/// Form being read, with the operands read so far
+type Form = (Operator, Vec<Prop>);
+
+enum Operator {
+    Not,
+    And,
+    Or,
+    Imply,
+    Equiv,
+    Apply(String),
+}
+impl Operator {
+    /// Number of operands, `None` for any number
+    fn arity(&self) -> Option<usize> {
+        match self {
+            Self::Not => Some(1),
+            Self::Apply(_) => None,
+            _ => Some(2),
+        }
+    }
+    fn apply(self, args: Vec<Prop>) -> Prop {
+        let mut args = args.into_iter();
+        let mut arg = || Box::new(args.next().expect("operand of the form"));
+        match self {
+            Self::Not => Prop::Implication(arg(), Prop::False.into()),
+            Self::And => Prop::Conjonction(arg(), arg()),
+            Self::Or => Prop::Disjonction(arg(), arg()),
+            Self::Imply => Prop::Implication(arg(), arg()),
+            Self::Equiv => Prop::Equivalence(arg(), arg()),
+            Self::Apply(name) => Prop::Defined(name, args.collect()),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &["not", "and", "or", "imply", "equiv", "apply"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_must_read_back_in_infix() {
+        let error = Sequent::from_sexpr(r#"(sequent (hyps "A->B") "A->B")"#).unwrap_err();
+        assert_eq!((error.message, error.span), ("invalid variable name", 15..21));
+        let error = Prop::from_sexpr(r#"(apply "X or" "A")"#).unwrap_err();
+        assert_eq!(error.message, "invalid connective name");
+        let prop = Prop::from_sexpr(r#"(apply "basics.Xor" "A" (not "B1"))"#).unwrap();
+        assert_eq!(Prop::from_sexpr(&prop.sexpr().to_string()).ok(), Some(prop.clone()));
+        assert_eq!(prop.repr().to_string().parse::<Prop>().ok(), Some(prop));
+    }
+
+    #[test]
+    fn deep_formulas_are_rejected() {
+        let deep = |n: usize, atom: &str| format!("{}{}{}", "(not ".repeat(n), atom, ")".repeat(n));
+        let prop = Prop::from_sexpr(&deep(1023, "\"A\"")).unwrap();
+        assert!(matches!(prop, Prop::Implication(_, _)));
+        let error = Prop::from_sexpr(&deep(1024, "\"A\"")).unwrap_err();
+        assert_eq!((error.message, error.span), ("formula nested too deeply", 5120..5123));
+        assert!(Prop::from_sexpr(&deep(1022, "true")).is_ok());
+        let error = Prop::from_sexpr(&deep(1023, "true")).unwrap_err();
+        assert_eq!(error.found.as_deref(), Some("true"));
+        let error = Prop::from_sexpr(&deep(2000, "\"A\"")).unwrap_err();
+        assert_eq!((error.message, error.span), ("formula nested too deeply", 5120..5121));
+    }
+
+    #[test]
+    fn operands_are_counted() {
+        let error = Prop::from_sexpr(r#"(not "A" "B")"#).unwrap_err();
+        assert_eq!((error.message, error.span), ("unexpected token", 9..12));
+        let error = Prop::from_sexpr(r#"(and "A")"#).unwrap_err();
+        assert_eq!((error.message, error.span), ("expecting a formula", 8..9));
+        let prop = Prop::from_sexpr(r#"(apply "Maj" "A" "B" (not false))"#).unwrap();
+        assert_eq!(prop, "Maj(A, B, ~!)".parse().unwrap());
+    }
+}
@@ -0,0 +1,252 @@
+use super::proof::{Centering, Proof};
+use super::symbols::ReprConf;
+use std::fmt::Write;
+
+const FONT_SIZE: f64 = 14.0;
+const RULE_FONT_SIZE: f64 = 11.0;
+const CHAR_WIDTH: f64 = FONT_SIZE * 0.6;
+const ROW_HEIGHT: f64 = FONT_SIZE * 1.6;
+const LINE_GAP: f64 = 6.0;
+const SPACER: f64 = 4.0 * CHAR_WIDTH;
+const MARGIN: f64 = 10.0;
+
+const STYLE: &str = "\
+text{font-family:monospace;white-space:pre}\
+.sequent{font-size:14px}\
+.goal{fill:#c03030}\
+.rule{font-size:11px;fill:#505050}\
+line{stroke:#000;stroke-width:1}";
+
+const SCRIPT: &str = "\
+document.querySelectorAll('.rule').forEach(function (rule) {\
+  rule.addEventListener('click', function () {\
+    var premises = rule.parentNode.querySelector(':scope > .premises');\
+    if (premises) {\
+      premises.classList.toggle('collapsed');\
+      rule.classList.toggle('folded');\
+    }\
+  });\
+});";
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn text_width(s: &str) -> f64 {
+    s.chars().count() as f64 * CHAR_WIDTH
+}
+
+/// Same layout as `ProofRender`, but in pixels, the origin of `body` is the
+/// bottom left corner of the layout
+struct SvgRender {
+    width: f64,
+    height: f64,
+    bottom_x: f64,
+    bottom_width: f64,
+    body: String,
+}
+impl SvgRender {
+    fn new() -> Self {
+        Self {
+            width: 0.0,
+            height: 0.0,
+            bottom_x: 0.0,
+            bottom_width: 0.0,
+            body: String::new(),
+        }
+    }
+    fn push_right(&mut self, rhs: &Self) {
+        self.bottom_width = self.width - self.bottom_x + rhs.bottom_x + rhs.bottom_width;
+        let _ = write!(
+            self.body,
+            "<g transform=\"translate({:.1},0)\">{}</g>",
+            self.width, rhs.body
+        );
+        self.width += rhs.width;
+        self.height = self.height.max(rhs.height);
+    }
+    fn from_iter(mut iter: impl Iterator<Item = Self>, spacer: f64) -> Self {
+        if let Some(mut r) = iter.next() {
+            for h in iter {
+                r.width += spacer;
+                r.push_right(&h);
+            }
+            r
+        } else {
+            Self::new()
+        }
+    }
+    fn from_proof(proof: &Proof, conf: ReprConf) -> Self {
+        let sequent = proof.sequent().repr_conf(conf).to_string();
+        let s_width = text_width(&sequent);
+        if let Some(rule) = proof.rule() {
+            let r = Self::from_iter(
+                rule.hypotheses().iter().map(|p| Self::from_proof(p, conf)),
+                SPACER,
+            );
+
+            let Centering {
+                premises_x: r_x,
+                sequent_x: s_x,
+                line_left,
+                line_right,
+            } = Centering::new(r.bottom_x, r.bottom_width, s_width);
+            let line_y = -ROW_HEIGHT - LINE_GAP / 2.0;
+            let symbol = rule.label(conf.unicode);
+
+            let mut body = String::from("<g class=\"node\">");
+            if !r.body.is_empty() {
+                let _ = write!(
+                    body,
+                    "<g class=\"premises\" transform=\"translate({:.1},{:.1})\">{}</g>",
+                    r_x,
+                    -ROW_HEIGHT - LINE_GAP,
+                    r.body
+                );
+            }
+            let _ = write!(
+                body,
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>\
+                 <text class=\"rule\" x=\"{:.1}\" y=\"{:.1}\">{}</text>\
+                 <text class=\"sequent\" x=\"{:.1}\" y=\"{:.1}\">{}</text></g>",
+                line_left,
+                line_y,
+                line_right,
+                line_y,
+                line_right + 2.0,
+                line_y + RULE_FONT_SIZE / 3.0,
+                escape(symbol),
+                s_x,
+                -ROW_HEIGHT / 3.0,
+                escape(&sequent),
+            );
+            let label_width = 2.0 + symbol.chars().count() as f64 * RULE_FONT_SIZE * 0.6;
+            Self {
+                width: (r.width + r_x).max(line_right + label_width),
+                height: r.height + ROW_HEIGHT + LINE_GAP,
+                bottom_x: s_x,
+                bottom_width: s_width,
+                body,
+            }
+        } else {
+            Self {
+                width: s_width,
+                height: ROW_HEIGHT,
+                bottom_x: 0.0,
+                bottom_width: s_width,
+                body: format!(
+                    "<g class=\"node\"><text class=\"sequent goal\" x=\"0\" y=\"{:.1}\">{}</text></g>",
+                    -ROW_HEIGHT / 3.0,
+                    escape(&sequent),
+                ),
+            }
+        }
+    }
+}
+
+impl Proof {
+    /// Renders the proof tree as a standalone SVG image, using unicode symbols
+    pub fn to_svg(&self) -> String {
+        let conf = ReprConf {
+            negation: true,
+            unicode: true,
+            ..Default::default()
+        };
+        let r = SvgRender::from_proof(self, conf);
+        let (width, height) = (r.width + 2.0 * MARGIN, r.height + 2.0 * MARGIN);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.1}\" height=\"{h:.1}\" \
+             viewBox=\"0 0 {w:.1} {h:.1}\"><style>{}</style>\
+             <g transform=\"translate({:.1},{:.1})\">{}</g></svg>",
+            STYLE,
+            MARGIN,
+            height - MARGIN,
+            r.body,
+            w = width,
+            h = height,
+        )
+    }
+    /// Renders the proof tree as a self-contained HTML page, clicking on the
+    /// name of a rule collapses or expands the proofs of its premises
+    pub fn to_html(&self) -> String {
+        let conf = ReprConf {
+            negation: true,
+            unicode: true,
+            ..Default::default()
+        };
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>.collapsed{{display:none}}.rule{{cursor:pointer}}\
+             .folded{{fill:#3050c0;font-weight:bold}}</style>\n</head>\n\
+             <body>\n{}\n<script>{}</script>\n</body>\n</html>\n",
+            escape(&self.sequent().repr_conf(conf).to_string()),
+            self.to_svg(),
+            SCRIPT,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProveBy;
+
+    fn conf(unicode: bool) -> ReprConf {
+        ReprConf {
+            negation: true,
+            unicode,
+            ..Default::default()
+        }
+    }
+
+    fn and_intro() -> Proof {
+        Proof::new("A, B |- A /\\ B".parse().unwrap(), ProveBy::ConjonctionIntroduction).unwrap()
+    }
+
+    /// `x` attribute of the last sequent, the conclusion of the proof
+    fn conclusion_x(body: &str) -> f64 {
+        let text = &body[body.rfind("class=\"sequent").unwrap()..];
+        let x = &text[text.find("x=\"").unwrap() + 3..];
+        x[..x.find('"').unwrap()].parse().unwrap()
+    }
+
+    #[test]
+    fn lays_out_like_the_text_rendering() {
+        let proof = and_intro();
+        let layout = proof.repr_conf(conf(true)).layout();
+        let bottom = layout.lines.last().unwrap();
+        let column = bottom.len() - bottom.trim_start().len();
+        assert!(column > 0);
+        let r = SvgRender::from_proof(&proof, conf(true));
+        assert!((conclusion_x(&r.body) - column as f64 * CHAR_WIDTH).abs() < 0.1);
+    }
+
+    #[test]
+    fn rule_labels_follow_the_conf() {
+        let proof = Proof::new("|- A -> A".parse().unwrap(), ProveBy::ImplicationIntroduction).unwrap();
+        let unicode = SvgRender::from_proof(&proof, conf(true)).body;
+        assert!(unicode.contains(">➔i</text>"));
+        let ascii = SvgRender::from_proof(&proof, conf(false)).body;
+        assert!(ascii.contains(">-&gt;i</text>"));
+        assert!(ascii.contains(">|- A-&gt;A</text>"));
+    }
+
+    #[test]
+    fn highlights_open_goals() {
+        let svg = and_intro().to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("class=\"sequent goal\"").count(), 2);
+        assert!(and_intro().to_html().contains(&svg));
+    }
+}
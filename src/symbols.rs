@@ -4,6 +4,9 @@ pub struct ReprConf {
     pub formated: bool,
     pub unicode: bool,
     pub emphazis: bool,
+    /// Sequents are shown as a numbered list of hypotheses, the numbers
+    /// given to `e <N>`, above their conclusion
+    pub numbered: bool,
 }
 
 use std::fmt;
@@ -32,15 +35,19 @@ pub enum Sym {
     RuleDisjonctionElimination,
     RuleDisjonctionWeakElimination,
     RuleEquivalenceIntroduction,
+    #[allow(dead_code)]
     RuleEquivalenceElimination,
     RuleEquivalenceWeakElimination,
     RuleConjonctionIntroduction,
     RuleConjonctionElimination,
     RuleConjonctionWeakElimination,
+    RuleReductioAdAbsurdum,
+    RuleUnfold,
+    RuleFold,
 }
 
 impl Sym {
-    pub const fn lex(&self) -> &str {
+    pub const fn lex(&self) -> &'static str {
         match self {
             Self::OpenParenthesis => "(",
             Self::CloseParenthesis => ")",
@@ -69,9 +76,12 @@ impl Sym {
             Self::RuleConjonctionIntroduction => "/\\i",
             Self::RuleConjonctionElimination => "/\\e",
             Self::RuleConjonctionWeakElimination => "/\\e'",
+            Self::RuleReductioAdAbsurdum => "raa",
+            Self::RuleUnfold => "unfold",
+            Self::RuleFold => "fold",
         }
     }
-    pub fn repr(&self, unicode: bool) -> &str {
+    pub fn repr(&self, unicode: bool) -> &'static str {
         match (unicode, self) {
             (true, Self::False) => "⊥",
             (true, Self::Conjonction) => "∧",
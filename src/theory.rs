@@ -0,0 +1,122 @@
+use super::definition::{prefixed, Definition};
+use super::error::{offset_in, ParseError};
+use super::problem::{is_name, Failure, Problem, ProblemError};
+use super::sequent::Sequent;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Problems of a file with the problems of the files it imports, eg: with
+/// `import "lib/basics.txt" as basics`, the theorem `contrapose` of
+/// `lib/basics.txt` is named `basics.contrapose`. Without `as`, the namespace
+/// is the name of the imported file without its extension. The connectives
+/// defined in `lib/basics.txt` are namespaced the same way, eg: `basics.Xor`.
+#[derive(Debug, Clone)]
+pub struct Theory {
+    /// Problems of the loaded file
+    pub problems: Vec<Problem>,
+    /// Problems of the imported files, with their namespaced names, `solve`
+    /// applies those solved in their file's session as lemmas
+    pub imported: Vec<Problem>,
+    /// Connectives defined in the loaded file, and in the imported ones with
+    /// their namespaced names, eg: `basics.Xor`
+    pub definitions: Vec<Definition>,
+}
+
+/// Error while loading a theory, with the file where it was found
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, io::Error),
+    /// `source` is the content of the file, to show the error with
+    /// `ParseError::snippet`
+    Parse {
+        path: PathBuf,
+        source: String,
+        error: Box<ProblemError>,
+    },
+}
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Self::Parse { path, error, .. } => {
+                write!(f, "{}:{}: {}", path.display(), error.line, error.error)
+            }
+        }
+    }
+}
+
+impl Theory {
+    /// Loads a problem file and its imports, paths are relative to the
+    /// importing file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| LoadError::Io(path.to_owned(), e))?;
+        load(path, &source, &mut Vec::new())
+    }
+}
+
+/// `stack` holds the files being loaded, to detect import cycles
+fn load(path: &Path, source: &str, stack: &mut Vec<PathBuf>) -> Result<Theory, LoadError> {
+    let error = |line: usize, part: &str, message: &'static str| LoadError::Parse {
+        path: path.to_owned(),
+        source: source.to_owned(),
+        error: Box::new(ProblemError {
+            line,
+            error: ParseError::new(0..part.len(), message)
+                .found(part)
+                .offset(offset_in(source, part)),
+        }),
+    };
+    stack.push(fs::canonicalize(path).unwrap_or_else(|_| path.to_owned()));
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut imported = Vec::new();
+    let mut namespaces: Vec<&str> = Vec::new();
+    let (mut problems, definitions) = Problem::parse_with(source, |line, file, namespace| {
+        let import_path = dir.join(file);
+        let namespace = match namespace {
+            Some(namespace) => namespace,
+            None => Path::new(file)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .filter(|stem| is_name(stem))
+                .ok_or_else(|| error(line, file, "file name is not a valid namespace, use `as`"))?,
+        };
+        if namespaces.contains(&namespace) {
+            return Err(error(line, file, "namespace already imported"));
+        }
+        let canonical = fs::canonicalize(&import_path)
+            .map_err(|_| error(line, file, "cannot read the imported file"))?;
+        if stack.contains(&canonical) {
+            return Err(error(line, file, "import cycle"));
+        }
+        let content = fs::read_to_string(&import_path)
+            .map_err(|_| error(line, file, "cannot read the imported file"))?;
+        let theory = load(&import_path, &content, stack)?;
+        for mut problem in theory.imported.into_iter().chain(theory.problems) {
+            problem.name = problem.name.map(|name| format!("{}.{}", namespace, name));
+            let hypotheses = problem.sequent.hypotheses().iter().map(|h| prefixed(h, namespace)).collect();
+            problem.sequent = Sequent::new(hypotheses, prefixed(problem.sequent.conclusion(), namespace));
+            problem.definitions = problem.definitions.iter().map(|d| d.namespaced(namespace)).collect();
+            imported.push(problem);
+        }
+        namespaces.push(namespace);
+        Ok(theory.definitions.iter().map(|d| d.namespaced(namespace)).collect())
+    })
+    .map_err(|e| match e {
+        Failure::Parse(error) => LoadError::Parse {
+            path: path.to_owned(),
+            source: source.to_owned(),
+            error: Box::new(error),
+        },
+        Failure::Import(e) => e,
+    })?;
+    stack.pop();
+    problems.iter_mut().for_each(|p| p.path = Some(path.to_owned()));
+    Ok(Theory {
+        problems,
+        imported,
+        definitions,
+    })
+}
@@ -0,0 +1,439 @@
+use super::error::ParseError;
+use super::property::{Prop, MAX_DEPTH};
+use super::sequent::Sequent;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),   // [a-z][A-Za-z0-9_]*
+    Upper(String),  // [A-Z][A-Za-z0-9_]*
+    Quoted(String), // '...'
+    Number(String), // [0-9]+
+    Dollar(String), // $[a-z]+
+    OpenParenthesis,
+    CloseParenthesis,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Dot,
+    Colon,
+    And,        // &
+    Or,         // |
+    Not,        // ~
+    Implies,    // =>
+    ImpliedBy,  // <=
+    Equivalent, // <=>
+    Xor,        // <~>
+    Nor,        // ~|
+    Nand,       // ~&
+    Forall,     // !
+    Exists,     // ?
+    Equal,      // =
+    NotEqual,   // !=
+}
+
+fn tokenize(s: &str) -> Result<Vec<(Token, Range<usize>)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut input = s.char_indices().peekable();
+    let unexpected = |i: usize, c: Option<char>| match c {
+        Some(c) => ParseError::new(i..i + c.len_utf8(), "unexpected character").found(&c.to_string()),
+        None => ParseError::new(s.len()..s.len(), "unexpected end of input").found(""),
+    };
+    while let Some((start, c)) = input.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '%' => {
+                while input.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '/' => {
+                match input.next() {
+                    Some((_, '*')) => {}
+                    Some((i, c)) => return Err(unexpected(i, Some(c)).expected(&["/*"])),
+                    None => return Err(unexpected(s.len(), None).expected(&["/*"])),
+                }
+                let mut last = ' ';
+                loop {
+                    match input.next() {
+                        Some((_, '/')) if last == '*' => break,
+                        Some((_, c)) => last = c,
+                        None => {
+                            return Err(ParseError::new(start..start + 2, "unterminated comment")
+                                .expected(&["*/"])
+                                .found(""))
+                        }
+                    }
+                }
+                continue;
+            }
+            '\'' => {
+                let mut name = String::new();
+                loop {
+                    match input.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, '\\')) => name.extend(input.next().map(|(_, c)| c)),
+                        Some((_, c)) => name.push(c),
+                        None => {
+                            return Err(ParseError::new(start..start + 1, "unterminated quoted name")
+                                .expected(&["'"])
+                                .found(""))
+                        }
+                    }
+                }
+                Token::Quoted(name)
+            }
+            '(' => Token::OpenParenthesis,
+            ')' => Token::CloseParenthesis,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            ',' => Token::Comma,
+            '.' => Token::Dot,
+            ':' => Token::Colon,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '?' => Token::Exists,
+            '=' => {
+                if input.next_if(|(_, c)| *c == '>').is_some() {
+                    Token::Implies
+                } else {
+                    Token::Equal
+                }
+            }
+            '!' => {
+                if input.next_if(|(_, c)| *c == '=').is_some() {
+                    Token::NotEqual
+                } else {
+                    Token::Forall
+                }
+            }
+            '~' => {
+                if input.next_if(|(_, c)| *c == '|').is_some() {
+                    Token::Nor
+                } else if input.next_if(|(_, c)| *c == '&').is_some() {
+                    Token::Nand
+                } else {
+                    Token::Not
+                }
+            }
+            '<' => {
+                if input.next_if(|(_, c)| *c == '~').is_some() {
+                    match input.next() {
+                        Some((_, '>')) => {}
+                        Some((i, c)) => return Err(unexpected(i, Some(c)).expected(&["<~>"])),
+                        None => return Err(unexpected(s.len(), None).expected(&["<~>"])),
+                    }
+                    Token::Xor
+                } else if input.next_if(|(_, c)| *c == '=').is_some() {
+                    if input.next_if(|(_, c)| *c == '>').is_some() {
+                        Token::Equivalent
+                    } else {
+                        Token::ImpliedBy
+                    }
+                } else {
+                    return Err(unexpected(start, Some(c)).expected(&["<=", "<=>", "<~>"]));
+                }
+            }
+            c if c.is_ascii_alphanumeric() || c == '$' => {
+                let mut name = String::new();
+                name.push(c);
+                while let Some((_, c)) =
+                    input.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    name.push(c);
+                }
+                match c {
+                    '$' => Token::Dollar(name),
+                    'a'..='z' => Token::Word(name),
+                    'A'..='Z' => Token::Upper(name),
+                    _ => Token::Number(name),
+                }
+            }
+            c => return Err(unexpected(start, Some(c))),
+        };
+        let end = input.peek().map(|(i, _)| *i).unwrap_or_else(|| s.len());
+        tokens.push((token, start..end));
+    }
+    Ok(tokens)
+}
+
+/// Maximum number of nested parentheses, they are parsed recursively even if
+/// they don't make the formula deeper
+const MAX_PARENTHESES: usize = 256;
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: std::iter::Peekable<std::vec::IntoIter<(Token, Range<usize>)>>,
+    /// Number of parentheses being parsed
+    parentheses: usize,
+}
+impl<'a> Parser<'a> {
+    fn peek(&mut self) -> Option<&Token> {
+        self.tokens.peek().map(|(token, _)| token)
+    }
+    /// Span of the next token if it is `token`
+    fn eat(&mut self, token: &Token) -> Option<Range<usize>> {
+        self.tokens.next_if(|(t, _)| t == token).map(|(_, span)| span)
+    }
+    /// Error on a token, or at the end of the input
+    fn error(&self, at: Option<Range<usize>>, message: &'static str) -> ParseError {
+        match at {
+            Some(span) => ParseError::new(span.clone(), message).found(&self.src[span]),
+            None => ParseError::new(self.src.len()..self.src.len(), message).found(""),
+        }
+    }
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), ParseError> {
+        match self.tokens.next() {
+            Some((t, _)) if t == token => Ok(()),
+            next => Err(self
+                .error(next.map(|(_, span)| span), "unexpected token")
+                .expected(&[expected])),
+        }
+    }
+    fn name(&mut self) -> Result<String, ParseError> {
+        match self.tokens.next() {
+            Some((Token::Word(name), _))
+            | Some((Token::Quoted(name), _))
+            | Some((Token::Number(name), _)) => Ok(name),
+            next => Err(self
+                .error(next.map(|(_, span)| span), "expecting a formula name")
+                .expected(&["name"])),
+        }
+    }
+    fn role(&mut self) -> Result<(String, Range<usize>), ParseError> {
+        match self.tokens.next() {
+            Some((Token::Word(role), span)) => Ok((role, span)),
+            next => Err(self
+                .error(next.map(|(_, span)| span), "expecting a formula role")
+                .expected(&["axiom", "conjecture"])),
+        }
+    }
+    /// Skips the optional annotations, up to the closing parenthesis
+    fn skip_annotations(&mut self) -> Result<(), ParseError> {
+        let mut level = 0;
+        loop {
+            match self.tokens.next() {
+                Some((Token::OpenParenthesis, _)) | Some((Token::OpenBracket, _)) => level += 1,
+                Some((Token::CloseParenthesis, _)) if level == 0 => return Ok(()),
+                Some((Token::CloseParenthesis, _)) | Some((Token::CloseBracket, _)) => level -= 1,
+                Some(_) => {}
+                None => {
+                    return Err(self
+                        .error(None, "closing parenthesis expected")
+                        .expected(&[")"]))
+                }
+            }
+        }
+    }
+    /// Checks the depth of a formula built from operands of depth `depth`,
+    /// `span` is the one of its connective
+    fn nested(&self, span: Range<usize>, prop: Prop, depth: usize) -> Result<(Prop, usize), ParseError> {
+        if depth > MAX_DEPTH {
+            Err(self.error(Some(span), "formula nested too deeply"))
+        } else {
+            Ok((prop, depth))
+        }
+    }
+    /// Starts parsing parentheses at `span`
+    fn enter(&mut self, span: Range<usize>) -> Result<(), ParseError> {
+        self.parentheses += 1;
+        if self.parentheses > MAX_PARENTHESES {
+            Err(self.error(Some(span), "formula nested too deeply"))
+        } else {
+            Ok(())
+        }
+    }
+    fn formula(&mut self) -> Result<(Prop, usize), ParseError> {
+        let (lhs, lhs_depth) = self.unitary()?;
+        match self.peek().cloned() {
+            Some(token @ Token::And) | Some(token @ Token::Or) => {
+                let (mut prop, mut depth) = (lhs, lhs_depth);
+                while let Some(span) = self.eat(&token) {
+                    let (rhs, rhs_depth) = self.unitary()?;
+                    let joined = if token == Token::And {
+                        prop.and(&rhs)
+                    } else {
+                        prop.or(&rhs)
+                    };
+                    (prop, depth) = self.nested(span, joined, depth.max(rhs_depth) + 1)?;
+                }
+                return Ok((prop, depth));
+            }
+            Some(Token::Implies) | Some(Token::ImpliedBy) | Some(Token::Equivalent) | Some(Token::Xor)
+            | Some(Token::Nor) | Some(Token::Nand) => {}
+            _ => return Ok((lhs, lhs_depth)),
+        }
+        let (connective, span) = self.tokens.next().expect("peeked connective");
+        let (rhs, rhs_depth) = self.unitary()?;
+        let depth = lhs_depth.max(rhs_depth) + 1;
+        match connective {
+            Token::Implies => self.nested(span, lhs.implies(&rhs), depth),
+            Token::ImpliedBy => self.nested(span, rhs.implies(&lhs), depth),
+            Token::Equivalent => self.nested(span, Prop::Equivalence(lhs.into(), rhs.into()), depth),
+            Token::Xor => self.nested(span, Prop::Equivalence(lhs.into(), rhs.into()).negate(), depth + 1),
+            Token::Nor => self.nested(span, lhs.or(&rhs).negate(), depth + 1),
+            _ => self.nested(span, lhs.and(&rhs).negate(), depth + 1),
+        }
+    }
+    fn unitary(&mut self) -> Result<(Prop, usize), ParseError> {
+        match self.tokens.next() {
+            Some((Token::Not, span)) => {
+                let mut negations = vec![span];
+                while let Some(span) = self.eat(&Token::Not) {
+                    negations.push(span);
+                }
+                let (mut prop, mut depth) = self.unitary()?;
+                for span in negations.into_iter().rev() {
+                    (prop, depth) = self.nested(span, prop.negate(), depth + 1)?;
+                }
+                Ok((prop, depth))
+            }
+            Some((Token::OpenParenthesis, span)) => {
+                self.enter(span)?;
+                let prop = self.formula()?;
+                self.expect(Token::CloseParenthesis, ")")?;
+                self.parentheses -= 1;
+                Ok(prop)
+            }
+            Some((Token::Word(name), span)) => {
+                if self.peek() == Some(&Token::OpenParenthesis) {
+                    Err(self.error(Some(span), "only propositional formulas are supported"))
+                } else {
+                    Ok((Prop::Variable(name), 1))
+                }
+            }
+            Some((Token::Dollar(name), span)) => match name.as_str() {
+                "$false" => Ok((Prop::False, 1)),
+                "$true" => Ok((Prop::False.negate(), 2)),
+                _ => Err(self
+                    .error(Some(span), "unknown defined proposition")
+                    .expected(&["$false", "$true"])),
+            },
+            Some((Token::Forall, span)) | Some((Token::Exists, span)) | Some((Token::Upper(_), span)) => {
+                Err(self.error(Some(span), "only propositional formulas are supported"))
+            }
+            Some((Token::Quoted(_), span)) => {
+                Err(self.error(Some(span), "quoted propositions are not supported"))
+            }
+            next => Err(self
+                .error(next.map(|(_, span)| span), "expecting a formula")
+                .expected(&["formula"])),
+        }
+    }
+}
+
+impl Sequent {
+    /// Reads a problem in the propositional subset of the TPTP format, eg:
+    ///
+    /// ```text
+    /// fof(a, axiom, p => q).
+    /// fof(b, axiom, p).
+    /// fof(goal, conjecture, q).
+    /// ```
+    ///
+    /// The axioms become the hypotheses and the conjecture becomes the
+    /// conclusion, a problem without conjecture concludes to false.
+    pub fn from_tptp(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            src: s,
+            tokens: tokenize(s)?.into_iter().peekable(),
+            parentheses: 0,
+        };
+        let mut hypotheses = Vec::new();
+        let mut conclusion = None;
+        while let Some((token, span)) = parser.tokens.next() {
+            match token {
+                Token::Word(ref language) if language == "fof" || language == "tff" => {}
+                Token::Word(ref include) if include == "include" => {
+                    return Err(parser.error(Some(span), "include directives are not supported"))
+                }
+                Token::Word(_) => {
+                    return Err(parser
+                        .error(Some(span), "only fof and tff formulas are supported")
+                        .expected(&["fof", "tff"]))
+                }
+                _ => {
+                    return Err(parser
+                        .error(Some(span), "expecting a formula declaration")
+                        .expected(&["fof", "tff"]))
+                }
+            }
+            parser.expect(Token::OpenParenthesis, "(")?;
+            parser.name()?;
+            parser.expect(Token::Comma, ",")?;
+            let (role, role_span) = parser.role()?;
+            parser.expect(Token::Comma, ",")?;
+            let (prop, _) = parser.formula()?;
+            match parser.tokens.next() {
+                Some((Token::CloseParenthesis, _)) => {}
+                Some((Token::Comma, _)) => parser.skip_annotations()?,
+                next => {
+                    return Err(parser
+                        .error(next.map(|(_, span)| span), "unexpected token")
+                        .expected(&[")"]))
+                }
+            }
+            parser.expect(Token::Dot, ".")?;
+            match role.as_str() {
+                "axiom" | "hypothesis" | "definition" | "assumption" | "lemma" | "theorem"
+                | "corollary" => hypotheses.push(prop),
+                "conjecture" => {
+                    if conclusion.replace(prop).is_some() {
+                        return Err(parser.error(Some(role_span), "expecting only one conjecture"));
+                    }
+                }
+                "negated_conjecture" => {
+                    return Err(parser.error(Some(role_span), "negated conjectures are not supported"))
+                }
+                _ => return Err(parser.error(Some(role_span), "unsupported formula role")),
+            }
+        }
+        Ok(Self::new(hypotheses, conclusion.unwrap_or(Prop::False)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conjecture(formula: &str) -> Result<Sequent, ParseError> {
+        Sequent::from_tptp(&format!("fof(goal, conjecture, {}).", formula))
+    }
+
+    fn sequent(s: &str) -> Sequent {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn negation_binds_tighter_than_binary_connectives() {
+        assert_eq!(conjecture("~ p & q").unwrap(), sequent("|- ~p /\\ q"));
+        assert_eq!(conjecture("~ (p & q)").unwrap(), sequent("|- ~(p /\\ q)"));
+        assert_eq!(conjecture("~ ~ p").unwrap(), sequent("|- ~~p"));
+        assert_eq!(conjecture("~ p => q").unwrap(), sequent("|- ~p -> q"));
+        assert_eq!(conjecture("p | q | r").unwrap(), sequent("|- (p \\/ q) \\/ r"));
+        assert_eq!(conjecture("(p | q) => r").unwrap(), sequent("|- p \\/ q -> r"));
+        assert_eq!(conjecture("p <= q").unwrap(), sequent("|- q -> p"));
+    }
+
+    #[test]
+    fn mixed_binary_connectives_need_parentheses() {
+        let s = "fof(goal, conjecture, p | q => r).";
+        let error = Sequent::from_tptp(s).unwrap_err();
+        assert_eq!(error.message, "unexpected token");
+        assert_eq!(&s[error.span], "=>");
+        assert!(conjecture("p & q | r").is_err());
+        assert!(conjecture("p => q => r").is_err());
+    }
+
+    #[test]
+    fn deep_formulas_are_rejected() {
+        assert!(conjecture(&format!("{}p", "~ ".repeat(1000))).is_ok());
+        let error = conjecture(&format!("{}p", "~ ".repeat(2000))).unwrap_err();
+        assert_eq!(error.message, "formula nested too deeply");
+        let error = conjecture(&format!("{}p{}", "(".repeat(300), ")".repeat(300))).unwrap_err();
+        assert_eq!(error.message, "formula nested too deeply");
+        let error = conjecture(&vec!["p"; 2000].join(" & ")).unwrap_err();
+        assert_eq!(error.message, "formula nested too deeply");
+        let shallow = conjecture(&format!("{}p{}", "(".repeat(200), ")".repeat(200)));
+        assert_eq!(shallow.unwrap(), sequent("|- p"));
+    }
+}